@@ -1,18 +1,147 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::cache::CacheMode;
+
+pub(crate) const DEFAULT_URL: &str = "https://start.spring.io";
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    after_help = "Answers are taken from, in order of precedence: the dedicated flags, --set, \
+                  SPRING_CLI_<STEP_ID> environment variables (e.g. SPRING_CLI_GROUP_ID), \
+                  the --profile and finally the server defaults.\n\n\
+                  Exit codes: 1 general error, 2 invalid arguments or answers, 3 server unreachable, \
+                  4 request rejected by the server, 5 project could not be written, 6 aborted by the user"
+)]
+#[command(args_conflicts_with_subcommands = true)]
 pub(crate) struct Args {
-    #[arg(short, long)]
-    #[clap(default_value = "https://start.spring.io")]
+    #[arg(short, long, global = true, env = "SPRING_CLI_URL")]
+    #[clap(default_value = DEFAULT_URL)]
     pub(crate) url: String,
 
+    /// Only use the cached metadata, never contact the server for it
+    #[arg(long, global = true, conflicts_with = "refresh_metadata")]
+    pub(crate) offline: bool,
+
+    /// Ignore the cached metadata and fetch it again
+    #[arg(long, global = true)]
+    pub(crate) refresh_metadata: bool,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+
+    #[command(flatten)]
+    pub(crate) generate: GenerateArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Print the options offered by the server
+    List {
+        #[arg(value_enum)]
+        what: ListKind,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Find dependency ids by fuzzy matching their name, group and description
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+
+        /// Maximum number of dependencies to print
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Show the details and documentation links of a dependency
+    Info {
+        dependency: String,
+
+        /// Boot version used for links and the compatibility check, defaults to the server's
+        #[arg(short = 'b', long)]
+        boot_version: Option<String>,
+    },
+    /// Generate the project into the current directory, merging it into an
+    /// existing repository. Generate options go after `init`
+    Init {
+        #[command(flatten)]
+        generate: Box<GenerateArgs>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ListKind {
+    Deps,
+    BootVersions,
+    JavaVersions,
+    Types,
+    Languages,
+    Packagings,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Plain,
+}
+
+/// What to do with files of the project that already exist on disk.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum OnConflict {
+    /// Fail when the target directory is not empty
+    #[default]
+    Fail,
+    /// Keep the existing files
+    Skip,
+    /// Replace the existing files
+    Overwrite,
+    /// Ask what to do when files would be replaced
+    Prompt,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct GenerateArgs {
     #[arg(short, long)]
     pub(crate) path: Option<String>,
 
+    /// What to do when extracting into a directory with existing files
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) on_conflict: OnConflict,
+
+    /// Leading path components to drop when extracting, by default the root
+    /// folder is dropped when the target directory has the same name. Fails
+    /// when the project is written as a single file or whole files would be
+    /// dropped
+    #[arg(long, value_name = "N")]
+    pub(crate) strip_components: Option<usize>,
+
     #[arg(long)]
     pub(crate) non_interactive: bool,
 
+    /// Print the generation url and equivalent commands instead of downloading the project
+    #[arg(long)]
+    pub(crate) print_url: bool,
+
+    /// Download the project and print the files it would write and the existing
+    /// files they conflict with, without writing anything
+    #[arg(long)]
+    pub(crate) dry_run: bool,
+
+    /// Prefill answers from a profile of the config file, flags still take precedence
+    #[arg(long)]
+    pub(crate) profile: Option<String>,
+
+    /// Comma separated step ids in the order to prompt them, unlisted steps going last
+    #[arg(long, value_delimiter = ',', value_name = "STEPS")]
+    pub(crate) step_order: Vec<String>,
+
     #[arg(short = 'l', long)]
     pub(crate) language: Option<String>,
 
@@ -43,9 +172,73 @@ pub(crate) struct Args {
     #[arg(short = 'D', long)]
     pub(crate) dependencies: Option<String>,
 
+    /// Comma separated dependencies to check when the dependency picker opens,
+    /// replacing dependencies set by --set, the environment or the profile
+    #[arg(long, conflicts_with = "dependencies", value_name = "DEPENDENCIES")]
+    pub(crate) seed_dependencies: Option<String>,
+
     #[arg(short = 't', long)]
     pub(crate) project_type: Option<String>,
 
     #[arg(long)]
     pub(crate) project_version: Option<String>,
+
+    /// Prefill any step offered by the server, e.g. `--set configurationFileFormat=yaml`
+    #[arg(long = "set", value_name = "STEP=VALUE", value_parser = parse_key_value)]
+    pub(crate) set: Vec<(String, String)>,
+}
+
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_owned(), value.to_owned()))
+        }
+        _ => Err(format!("expected STEP=VALUE, got '{}'", s)),
+    }
+}
+
+impl Args {
+    pub(crate) fn cache_mode(&self) -> CacheMode {
+        if self.offline {
+            CacheMode::Offline
+        } else if self.refresh_metadata {
+            CacheMode::Refresh
+        } else {
+            CacheMode::Revalidate
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_args() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_generate_flags_conflict_with_subcommands() {
+        let err = Args::try_parse_from(["spring-boot-cli", "-g", "com.acme", "list", "deps"])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        let args = Args::try_parse_from(["spring-boot-cli", "list", "deps", "--offline"]).unwrap();
+        assert!(matches!(args.command, Some(Command::List { .. })));
+        assert_eq!(args.cache_mode(), CacheMode::Offline);
+    }
+
+    #[test]
+    fn test_generate_flags_before_init_are_rejected() {
+        let err = Args::try_parse_from(["spring-boot-cli", "-g", "com.acme", "init"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        let args = Args::try_parse_from(["spring-boot-cli", "init", "-g", "com.acme"]).unwrap();
+        let Some(Command::Init { generate }) = args.command else {
+            panic!("expected init");
+        };
+        assert_eq!(generate.group_id.as_deref(), Some("com.acme"));
+    }
 }
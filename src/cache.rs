@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Revalidate the cached metadata and fall back to it when the server is unreachable.
+    Revalidate,
+    /// Only use the cached metadata, never touch the network.
+    Offline,
+    /// Ignore the cached metadata and fetch it again.
+    Refresh,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedMetadata {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    dir: PathBuf,
+}
+
+impl MetadataCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        MetadataCache { dir: dir.into() }
+    }
+
+    /// The per-user cache directory, e.g. `$XDG_CACHE_HOME/spring-boot-cli` on Linux.
+    pub fn default_location() -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::new(dir.join("spring-boot-cli")))
+    }
+
+    pub fn load(&self, url: &str) -> Option<CachedMetadata> {
+        let content = fs::read(self.path_for(url)).ok()?;
+        let cached: CachedMetadata = serde_json::from_slice(&content).ok()?;
+        (cached.url == url).then_some(cached)
+    }
+
+    pub fn store(&self, cached: &CachedMetadata) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("could not create cache dir {}", self.dir.display()))?;
+        let path = self.path_for(&cached.url);
+        fs::write(&path, serde_json::to_vec(cached)?)
+            .with_context(|| format!("could not write cache file {}", path.display()))
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        let key: String = url
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c,
+                _ => '_',
+            })
+            .collect();
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn test_store_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::new(dir.path());
+        let cached = CachedMetadata {
+            url: "https://start.spring.io/metadata/config".to_owned(),
+            etag: Some("\"abc\"".to_owned()),
+            last_modified: None,
+            body: json!({"name": {"id": "name", "type": "TEXT", "content": "demo"}}),
+        };
+
+        cache.store(&cached).unwrap();
+
+        assert_eq!(
+            cache.load("https://start.spring.io/metadata/config"),
+            Some(cached)
+        );
+        assert_eq!(cache.load("https://example.com/metadata/config"), None);
+    }
+
+    #[test]
+    fn test_urls_get_separate_files() {
+        let cache = MetadataCache::new("/tmp/cache");
+
+        assert_ne!(
+            cache.path_for("https://start.spring.io/metadata/config"),
+            cache.path_for("http://localhost:8080/metadata/config")
+        );
+        assert_eq!(
+            cache.path_for("http://localhost:8080/metadata/config"),
+            Path::new("/tmp/cache/http___localhost_8080_metadata_config.json")
+        );
+    }
+}
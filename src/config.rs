@@ -0,0 +1,337 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::error::CliError;
+
+/// Name of the project-local config file, looked up in the current directory.
+pub const LOCAL_CONFIG: &str = ".spring-boot-cli.toml";
+
+/// Prefix of the environment variables prefilling steps, e.g. `SPRING_CLI_GROUP_ID`.
+pub const ENV_PREFIX: &str = "SPRING_CLI_";
+
+/// Config read from `~/.config/spring-boot-cli/config.toml` (or
+/// `$XDG_CONFIG_HOME/spring-boot-cli/config.toml`) on every platform and
+/// `.spring-boot-cli.toml`:
+///
+/// ```toml
+/// [profiles.team]
+/// groupId = "com.acme"
+/// javaVersion = "21"
+/// type = "maven-project"
+/// dependencies = ["web", "actuator"]
+///
+/// [profiles.starter]
+/// seed-dependencies = ["web", "actuator"]
+/// ```
+///
+/// Profile keys are step ids such as `groupId` or `dependencies`. Servers with
+/// other steps can set the prompt order with a top-level
+/// `step-order = ["type", "groupId", ...]`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    pub step_order: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Profile {
+    /// Dependencies checked when the dependency picker opens, unlike
+    /// `dependencies` which skips the picker.
+    #[serde(rename = "seed-dependencies")]
+    pub seed_dependencies: Option<ProfileValue>,
+    #[serde(flatten)]
+    pub values: BTreeMap<String, ProfileValue>,
+}
+
+impl Profile {
+    /// The prefill values of the profile, keyed by step id.
+    pub fn prefills(&self) -> HashMap<String, String> {
+        self.values
+            .iter()
+            .map(|(key, value)| (key.clone(), value.to_prefill()))
+            .collect()
+    }
+
+    pub fn seed_dependencies(&self) -> Option<String> {
+        self.seed_dependencies
+            .as_ref()
+            .map(ProfileValue::to_prefill)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ProfileValue {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl ProfileValue {
+    fn to_prefill(&self) -> String {
+        match self {
+            ProfileValue::One(value) => value.clone(),
+            ProfileValue::Many(values) => values.join(","),
+        }
+    }
+}
+
+/// The user config file, `~/.config` being used on macOS and Windows too so the
+/// path is the same everywhere.
+fn user_config(xdg_config_home: Option<PathBuf>, home: Option<PathBuf>) -> Option<PathBuf> {
+    xdg_config_home
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(".config")))
+        .map(|dir| dir.join("spring-boot-cli").join("config.toml"))
+}
+
+impl Config {
+    /// The user config merged with the config of the current directory, the
+    /// latter winning for keys set in both.
+    pub fn load() -> Result<Self> {
+        let global = user_config(
+            std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+            dirs::home_dir(),
+        );
+        let local = PathBuf::from(LOCAL_CONFIG);
+        Self::from_files(global.iter().chain([&local]))
+    }
+
+    fn from_files<'a>(paths: impl IntoIterator<Item = &'a PathBuf>) -> Result<Self> {
+        let mut config = Config::default();
+        for path in paths {
+            if let Some(other) = Self::from_file(path)? {
+                config.merge(other);
+            }
+        }
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Option<Self>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("could not read {}", path.display()))
+            }
+        };
+        toml::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    fn merge(&mut self, other: Config) {
+        if other.step_order.is_some() {
+            self.step_order = other.step_order;
+        }
+        for (name, profile) in other.profiles {
+            let merged = self.profiles.entry(name).or_default();
+            if profile.seed_dependencies.is_some() {
+                merged.seed_dependencies = profile.seed_dependencies;
+            }
+            merged.values.extend(profile.values);
+        }
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        let Some(profile) = self.profiles.get(name) else {
+            let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            let message = if available.is_empty() {
+                format!("unknown profile '{}', no profiles are configured", name)
+            } else {
+                format!(
+                    "unknown profile '{}', available profiles: {}",
+                    name,
+                    available.join(", ")
+                )
+            };
+            return Err(CliError::Invalid(message).into());
+        };
+        Ok(profile)
+    }
+}
+
+/// The environment variable for a step id, `groupId` becoming `SPRING_CLI_GROUP_ID`.
+pub fn env_var_name(step_id: &str) -> String {
+    let mut name = ENV_PREFIX.to_owned();
+    let mut previous_lower = false;
+    for c in step_id.chars() {
+        if c.is_uppercase() && previous_lower {
+            name.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        if c.is_alphanumeric() {
+            name.extend(c.to_uppercase());
+        } else {
+            name.push('_');
+        }
+    }
+    name
+}
+
+/// The prefill values set through environment variables for the given step ids.
+pub fn env_values<'a>(
+    step_ids: impl IntoIterator<Item = &'a str>,
+    var: impl Fn(&str) -> Option<String>,
+) -> HashMap<String, String> {
+    step_ids
+        .into_iter()
+        .filter_map(|id| var(&env_var_name(id)).map(|value| (id.to_owned(), value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_user_config() {
+        assert_eq!(
+            user_config(None, Some(PathBuf::from("/home/dev"))),
+            Some(PathBuf::from(
+                "/home/dev/.config/spring-boot-cli/config.toml"
+            ))
+        );
+        assert_eq!(
+            user_config(
+                Some(PathBuf::from("/xdg")),
+                Some(PathBuf::from("/home/dev"))
+            ),
+            Some(PathBuf::from("/xdg/spring-boot-cli/config.toml"))
+        );
+        assert_eq!(
+            user_config(
+                Some(PathBuf::from("relative")),
+                Some(PathBuf::from("/home/dev"))
+            ),
+            Some(PathBuf::from(
+                "/home/dev/.config/spring-boot-cli/config.toml"
+            ))
+        );
+        assert_eq!(user_config(None, None), None);
+    }
+
+    #[test]
+    fn test_profile_values() {
+        let config: Config = toml::from_str(
+            r#"
+            [profiles.team]
+            groupId = "com.acme"
+            javaVersion = "21"
+            dependencies = ["web", "actuator"]
+
+            [profiles.starter]
+            seed-dependencies = ["web", "actuator"]
+            "#,
+        )
+        .unwrap();
+
+        let values = config.profile("team").unwrap().prefills();
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(values["groupId"], "com.acme");
+        assert_eq!(values["javaVersion"], "21");
+        assert_eq!(values["dependencies"], "web,actuator");
+
+        let starter = config.profile("starter").unwrap();
+        assert!(starter.prefills().is_empty());
+        assert_eq!(starter.seed_dependencies().unwrap(), "web,actuator");
+    }
+
+    #[test]
+    fn test_local_config_overrides_global() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = write(
+            dir.path(),
+            "config.toml",
+            r#"
+            [profiles.team]
+            groupId = "com.acme"
+            javaVersion = "17"
+
+            [profiles.other]
+            language = "kotlin"
+            "#,
+        );
+        let local = write(
+            dir.path(),
+            "local.toml",
+            r#"
+            step-order = ["groupId", "artifactId"]
+
+            [profiles.team]
+            javaVersion = "21"
+            "#,
+        );
+        let missing = dir.path().join("missing.toml");
+
+        let config = Config::from_files([&global, &missing, &local]).unwrap();
+
+        let values = config.profile("team").unwrap().prefills();
+        assert_eq!(values["groupId"], "com.acme");
+        assert_eq!(values["javaVersion"], "21");
+        assert_eq!(
+            config.step_order,
+            Some(vec!["groupId".to_owned(), "artifactId".to_owned()])
+        );
+        assert_eq!(
+            config.profile("nope").unwrap_err().to_string(),
+            "unknown profile 'nope', available profiles: other, team"
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(dir.path(), "config.toml", "[profiles.team]\ngroupId = 1\n");
+
+        let err = Config::from_files([&path]).unwrap_err();
+
+        assert!(err.to_string().starts_with("invalid config"));
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("groupId"), "SPRING_CLI_GROUP_ID");
+        assert_eq!(env_var_name("dependencies"), "SPRING_CLI_DEPENDENCIES");
+        assert_eq!(env_var_name("type"), "SPRING_CLI_TYPE");
+        assert_eq!(
+            env_var_name("configurationFileFormat"),
+            "SPRING_CLI_CONFIGURATION_FILE_FORMAT"
+        );
+        assert_eq!(
+            env_var_name("company.build-tool"),
+            "SPRING_CLI_COMPANY_BUILD_TOOL"
+        );
+    }
+
+    #[test]
+    fn test_env_values() {
+        let vars: HashMap<&str, &str> = [
+            ("SPRING_CLI_GROUP_ID", "com.acme"),
+            ("SPRING_CLI_DEPENDENCIES", "web,actuator"),
+        ]
+        .into_iter()
+        .collect();
+
+        let values = env_values(["groupId", "dependencies", "javaVersion"], |name| {
+            vars.get(name).map(|v| v.to_string())
+        });
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["groupId"], "com.acme");
+        assert_eq!(values["dependencies"], "web,actuator");
+    }
+}
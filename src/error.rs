@@ -0,0 +1,139 @@
+use std::{error::Error, fmt};
+
+/// Failures that map to their own process exit code so scripts can tell
+/// them apart.
+#[derive(Debug)]
+pub enum CliError {
+    /// An argument or prefilled value is not valid.
+    Invalid(String),
+    /// The server could not be reached.
+    Network(ureq::Error),
+    /// The server answered with an error status.
+    Server { status: u16, message: String },
+    /// The project could not be written to disk.
+    Write(anyhow::Error),
+    /// The user chose to abort.
+    Aborted(String),
+}
+
+impl CliError {
+    pub const GENERAL_EXIT_CODE: u8 = 1;
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Invalid(_) => 2,
+            CliError::Network(_) => 3,
+            CliError::Server { .. } => 4,
+            CliError::Write(_) => 5,
+            CliError::Aborted(_) => 6,
+        }
+    }
+
+    /// Builds a server error from an Initializr error body such as
+    /// `{"status":400,"error":"Bad Request","message":"Invalid Spring Boot version"}`,
+    /// falling back to the raw body when it is not JSON.
+    pub fn from_response(status: u16, body: &str) -> Self {
+        let json: Option<serde_json::Value> = serde_json::from_str(body).ok();
+        let field = |name: &str| {
+            json.as_ref()
+                .and_then(|json| json[name].as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_owned)
+        };
+        let message = field("message")
+            .or_else(|| field("error"))
+            .unwrap_or_else(|| body.trim().to_owned());
+        CliError::Server { status, message }
+    }
+
+    /// The exit code for an error, looking through its whole chain.
+    pub fn exit_code_of(err: &anyhow::Error) -> u8 {
+        err.chain()
+            .find_map(|e| e.downcast_ref::<CliError>())
+            .map_or(Self::GENERAL_EXIT_CODE, CliError::exit_code)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Invalid(message) | CliError::Aborted(message) => write!(f, "{}", message),
+            CliError::Network(_) => write!(f, "could not reach the server"),
+            CliError::Server { status, message } if message.is_empty() => {
+                write!(f, "server rejected the request with status {}", status)
+            }
+            CliError::Server { status, message } => {
+                write!(f, "server rejected the request ({}): {}", status, message)
+            }
+            CliError::Write(_) => write!(f, "could not write the project"),
+        }
+    }
+}
+
+impl Error for CliError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CliError::Invalid(_) | CliError::Server { .. } | CliError::Aborted(_) => None,
+            CliError::Network(err) => Some(err),
+            CliError::Write(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_response_uses_message() {
+        let err = CliError::from_response(
+            400,
+            r#"{"timestamp":"2025-02-01T10:00:00.000+00:00","status":400,"error":"Bad Request","message":"Dependency 'x' is not compatible with Spring Boot 3.5.0","path":"/starter.zip"}"#,
+        );
+
+        assert_eq!(
+            err.to_string(),
+            "server rejected the request (400): Dependency 'x' is not compatible with Spring Boot 3.5.0"
+        );
+    }
+
+    #[test]
+    fn test_from_response_falls_back() {
+        let err = CliError::from_response(500, r#"{"status":500,"error":"Internal Server Error"}"#);
+        assert_eq!(
+            err.to_string(),
+            "server rejected the request (500): Internal Server Error"
+        );
+
+        let err = CliError::from_response(502, "Bad Gateway\n");
+        assert_eq!(
+            err.to_string(),
+            "server rejected the request (502): Bad Gateway"
+        );
+
+        let err = CliError::from_response(404, "");
+        assert_eq!(
+            err.to_string(),
+            "server rejected the request with status 404"
+        );
+    }
+
+    #[test]
+    fn test_exit_code_of() {
+        let err = anyhow::Error::from(CliError::from_response(400, ""));
+        assert_eq!(CliError::exit_code_of(&err), 4);
+
+        let err = anyhow::Error::from(CliError::Write(anyhow::anyhow!("disk full")))
+            .context("while writing ./demo");
+        assert_eq!(CliError::exit_code_of(&err), 5);
+
+        let err = anyhow::Error::from(CliError::Invalid("invalid groupId 'a..b'".to_owned()));
+        assert_eq!(CliError::exit_code_of(&err), 2);
+
+        let err = anyhow::Error::from(CliError::Aborted("aborted".to_owned()));
+        assert_eq!(CliError::exit_code_of(&err), 6);
+
+        let err = anyhow::anyhow!("other").context("wrapped");
+        assert_eq!(CliError::exit_code_of(&err), CliError::GENERAL_EXIT_CODE);
+    }
+}
@@ -0,0 +1,759 @@
+use anyhow::{Context, Result};
+use inquire::{InquireError, Select};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Cursor, Write},
+    path::{Path, PathBuf},
+};
+use zip::ZipArchive;
+
+use crate::{args::OnConflict, error::CliError};
+
+/// An archive entry with its path resolved under the target directory.
+struct Entry {
+    index: usize,
+    path: PathBuf,
+    is_dir: bool,
+    exists: bool,
+}
+
+/// Resolves every entry under `target` without its first `strip` path
+/// components, refusing entries that would escape it or files stripped
+/// entirely.
+fn plan(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    target: &Path,
+    strip: usize,
+) -> Result<Vec<Entry>> {
+    let mut entries = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let file = archive.by_index(index)?;
+        let Some(relative) = file.enclosed_name() else {
+            return Err(CliError::Invalid(format!(
+                "refusing to extract '{}' outside of {}",
+                file.name(),
+                target.display()
+            ))
+            .into());
+        };
+        let relative: PathBuf = relative.components().skip(strip).collect();
+        if relative.as_os_str().is_empty() && !file.is_dir() {
+            return Err(CliError::Invalid(format!(
+                "stripping {} path components leaves nothing of '{}', pass a smaller \
+                 --strip-components",
+                strip,
+                file.name()
+            ))
+            .into());
+        }
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let path = target.join(relative);
+        entries.push(Entry {
+            index,
+            exists: !file.is_dir() && path.exists(),
+            is_dir: file.is_dir(),
+            path,
+        });
+    }
+    Ok(entries)
+}
+
+/// The directory every entry of the archive lives in, like the `<artifactId>/`
+/// folder of start.spring.io archives.
+fn root_dir<'a>(archive: &'a ZipArchive<Cursor<&[u8]>>) -> Option<&'a str> {
+    let mut roots = archive
+        .file_names()
+        .map(|name| name.split_once('/').map(|(root, _)| root));
+    let root = roots.next()??;
+    roots.all(|other| other == Some(root)).then_some(root)
+}
+
+/// Strips the root directory when the target is already named after it, so
+/// `-p ./demo` gives `./demo/pom.xml` rather than `./demo/demo/pom.xml`.
+fn default_strip(archive: &ZipArchive<Cursor<&[u8]>>, target: &Path) -> usize {
+    match (root_dir(archive), target.file_name()) {
+        (Some(root), Some(name)) if name == root => 1,
+        _ => 0,
+    }
+}
+
+fn is_non_empty_dir(path: &Path) -> Result<bool> {
+    match fs::read_dir(path) {
+        Ok(mut entries) => Ok(entries.next().is_some()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err).with_context(|| format!("could not read {}", path.display())),
+    }
+}
+
+fn list(paths: &[&Path]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("  {}", path.display()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const OVERWRITE: &str = "Overwrite them";
+const SKIP: &str = "Keep the existing files";
+const ABORT: &str = "Abort";
+
+/// Asks whether to overwrite the existing files, Esc aborting like the Abort
+/// choice.
+fn ask_overwrite(target: &Path) -> Result<bool> {
+    match Select::new("What do you want to do?", vec![OVERWRITE, SKIP, ABORT]).prompt() {
+        Ok(OVERWRITE) => Ok(true),
+        Ok(SKIP) => Ok(false),
+        Ok(_) | Err(InquireError::OperationCanceled) => Err(CliError::Aborted(format!(
+            "aborted, {} was left untouched",
+            target.display()
+        ))
+        .into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Scripts that have to stay executable however the archive stores them.
+const WRAPPER_SCRIPTS: &[&str] = &["mvnw", "gradlew"];
+
+/// The permissions of an extracted file: the Unix mode stored in the archive,
+/// without setuid and friends, with the executable bit forced on build
+/// wrapper scripts.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn file_mode(path: &Path, stored: Option<u32>) -> Option<u32> {
+    let wrapper = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| WRAPPER_SCRIPTS.contains(&name));
+    match stored.map(|mode| mode & 0o777).filter(|mode| *mode != 0) {
+        Some(mode) if wrapper => Some(mode | 0o111),
+        Some(mode) => Some(mode),
+        None if wrapper => Some(0o755),
+        None => None,
+    }
+}
+
+/// How to extract a project archive.
+pub(crate) struct Extraction {
+    pub(crate) on_conflict: OnConflict,
+    pub(crate) strip_components: Option<usize>,
+    /// Merge into an existing directory like a repository: the directory
+    /// may be non-empty and `.gitignore` files are merged line by line.
+    pub(crate) merge: bool,
+}
+
+fn is_gitignore(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == ".gitignore")
+}
+
+/// Appends the lines of `incoming` that `existing` does not have yet, keeping
+/// their order and at most one blank line in a row.
+fn merge_gitignore(existing: &str, incoming: &str) -> String {
+    let present: Vec<&str> = existing.lines().map(str::trim).collect();
+    let mut added: Vec<&str> = Vec::new();
+    for line in incoming.lines() {
+        let blank = line.trim().is_empty();
+        if blank && added.last().is_none_or(|last| last.trim().is_empty()) {
+            continue;
+        }
+        if blank || !present.contains(&line.trim()) {
+            added.push(line);
+        }
+    }
+    while added.last().is_some_and(|last| last.trim().is_empty()) {
+        added.pop();
+    }
+
+    let mut merged = existing.to_owned();
+    if added.is_empty() {
+        return merged;
+    }
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    if !merged.is_empty() {
+        merged.push('\n');
+    }
+    merged.push_str(&added.join("\n"));
+    merged.push('\n');
+    merged
+}
+
+/// The number of leading path components to drop: the given one, else the
+/// root directory in merge mode or when the target is named after it.
+fn strip_components(
+    archive: &ZipArchive<Cursor<&[u8]>>,
+    target: &Path,
+    options: &Extraction,
+) -> usize {
+    options.strip_components.unwrap_or_else(|| {
+        if options.merge {
+            root_dir(archive).map_or(0, |_| 1)
+        } else {
+            default_strip(archive, target)
+        }
+    })
+}
+
+fn is_merged(entry: &Entry, options: &Extraction) -> bool {
+    options.merge && entry.exists && is_gitignore(&entry.path)
+}
+
+/// The existing files the entries would replace.
+fn conflicts<'a>(entries: &'a [Entry], options: &Extraction) -> Vec<&'a Path> {
+    entries
+        .iter()
+        .filter(|entry| entry.exists && !is_merged(entry, options))
+        .map(|entry| entry.path.as_path())
+        .collect()
+}
+
+/// Extracts the project into `target`, handling files that already exist
+/// according to the conflict policy.
+pub(crate) fn extract(zip: &[u8], target: &Path, options: &Extraction) -> Result<()> {
+    let mut archive = ZipArchive::new(Cursor::new(zip))?;
+    let strip = strip_components(&archive, target, options);
+    let entries = plan(&mut archive, target, strip)?;
+    let merged = |entry: &Entry| is_merged(entry, options);
+    let conflicts = conflicts(&entries, options);
+
+    let overwrite = match options.on_conflict {
+        OnConflict::Fail if options.merge && !conflicts.is_empty() => {
+            return Err(CliError::Invalid(format!(
+                "these files already exist in {}, pass --on-conflict to choose what to do \
+                 with them:\n{}",
+                target.display(),
+                list(&conflicts)
+            ))
+            .into());
+        }
+        OnConflict::Fail if !options.merge && is_non_empty_dir(target)? => {
+            let message = if conflicts.is_empty() {
+                format!(
+                    "{} is not empty, pass --on-conflict to extract into it anyway",
+                    target.display()
+                )
+            } else {
+                format!(
+                    "{} is not empty and these files would be overwritten, pass --on-conflict \
+                     to choose what to do with them:\n{}",
+                    target.display(),
+                    list(&conflicts)
+                )
+            };
+            return Err(CliError::Invalid(message).into());
+        }
+        OnConflict::Fail | OnConflict::Overwrite => true,
+        OnConflict::Skip => false,
+        OnConflict::Prompt if conflicts.is_empty() => true,
+        OnConflict::Prompt => {
+            println!("These files already exist:\n{}", list(&conflicts));
+            ask_overwrite(target)?
+        }
+    };
+
+    fs::create_dir_all(target)?;
+    println!("writing data to {}", target.display());
+    for entry in &entries {
+        if entry.is_dir {
+            fs::create_dir_all(&entry.path)?;
+            continue;
+        }
+        let mut file = archive.by_index(entry.index)?;
+        if merged(entry) {
+            let mut incoming = String::new();
+            io::Read::read_to_string(&mut file, &mut incoming)?;
+            let existing = fs::read_to_string(&entry.path)?;
+            fs::write(&entry.path, merge_gitignore(&existing, &incoming))?;
+            println!("merged {}", entry.path.display());
+            continue;
+        }
+        if entry.exists && !overwrite {
+            continue;
+        }
+        if let Some(parent) = entry.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = fs::File::create(&entry.path)
+            .with_context(|| format!("could not create {}", entry.path.display()))?;
+        io::copy(&mut file, &mut out)?;
+        #[cfg(unix)]
+        if let Some(mode) = file_mode(&entry.path, file.unix_mode()) {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&entry.path, fs::Permissions::from_mode(mode))?;
+        }
+    }
+
+    if !conflicts.is_empty() {
+        let action = if overwrite { "overwrote" } else { "skipped" };
+        println!("{} existing files:\n{}", action, list(&conflicts));
+    }
+    Ok(())
+}
+
+/// Writes a single file such as a build file or an archive kept as is,
+/// handling an existing file according to the conflict policy.
+pub(crate) fn write_file(path: &Path, content: &[u8], on_conflict: OnConflict) -> Result<()> {
+    let exists = path.exists();
+    if exists {
+        let overwrite = match on_conflict {
+            OnConflict::Fail => {
+                return Err(CliError::Invalid(format!(
+                    "{} already exists, pass --on-conflict to choose what to do with it",
+                    path.display()
+                ))
+                .into())
+            }
+            OnConflict::Overwrite => true,
+            OnConflict::Skip => false,
+            OnConflict::Prompt => {
+                println!("{} already exists", path.display());
+                ask_overwrite(path)?
+            }
+        };
+        if !overwrite {
+            println!("skipped existing file {}", path.display());
+            return Ok(());
+        }
+    }
+
+    let parent = path.parent().context("dident find parent of file")?;
+    fs::create_dir_all(parent)?;
+    println!("writing data to {}", path.display());
+    fs::write(path, content).with_context(|| format!("could not create {}", path.display()))?;
+    if exists {
+        println!("overwrote existing file {}", path.display());
+    }
+    Ok(())
+}
+
+fn human_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Prints the entries of the archive as a tree with their sizes, without
+/// writing anything.
+pub(crate) fn print_tree(zip: &[u8], out: &mut impl Write) -> Result<()> {
+    let mut archive = ZipArchive::new(Cursor::new(zip))?;
+    let base = Path::new(".");
+    let entries = plan(&mut archive, base, 0)?;
+    write_tree(&mut archive, &entries, base, out)
+}
+
+/// Prints the files extraction would write into `target`, as a tree, and the
+/// existing files they would conflict with, without writing anything.
+pub(crate) fn print_plan(
+    zip: &[u8],
+    target: &Path,
+    options: &Extraction,
+    out: &mut impl Write,
+) -> Result<()> {
+    let mut archive = ZipArchive::new(Cursor::new(zip))?;
+    let strip = strip_components(&archive, target, options);
+    let entries = plan(&mut archive, target, strip)?;
+    write_tree(&mut archive, &entries, target, out)?;
+
+    let merged: Vec<&Path> = entries
+        .iter()
+        .filter(|entry| is_merged(entry, options))
+        .map(|entry| entry.path.as_path())
+        .collect();
+    if !merged.is_empty() {
+        writeln!(out, "would merge:\n{}", list(&merged))?;
+    }
+    let conflicts = conflicts(&entries, options);
+    if !conflicts.is_empty() {
+        writeln!(out, "would conflict with:\n{}", list(&conflicts))?;
+    }
+    Ok(())
+}
+
+/// Writes the entries as a tree of their paths relative to `base`.
+fn write_tree(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    entries: &[Entry],
+    base: &Path,
+    out: &mut impl Write,
+) -> Result<()> {
+    // Directories map to `None`, including ones only implied by file paths
+    let mut tree: BTreeMap<&Path, Option<u64>> = BTreeMap::new();
+    for entry in entries {
+        let path = entry.path.strip_prefix(base).unwrap_or(&entry.path);
+        for ancestor in path.ancestors().skip(1) {
+            if !ancestor.as_os_str().is_empty() {
+                tree.insert(ancestor, None);
+            }
+        }
+        let size = archive.by_index(entry.index)?.size();
+        tree.insert(path, (!entry.is_dir).then_some(size));
+    }
+
+    for (path, size) in &tree {
+        let indent = "  ".repeat(path.components().count().saturating_sub(1));
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match size {
+            Some(size) => writeln!(out, "{}{} ({})", indent, name, human_size(*size))?,
+            None => writeln!(out, "{}{}/", indent, name)?,
+        }
+    }
+    let sizes: Vec<u64> = tree.values().flatten().copied().collect();
+    writeln!(
+        out,
+        "{} files, {}",
+        sizes.len(),
+        human_size(sizes.iter().sum())
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn options(on_conflict: OnConflict, strip_components: Option<usize>) -> Extraction {
+        Extraction {
+            on_conflict,
+            strip_components,
+            merge: false,
+        }
+    }
+
+    fn project() -> Vec<u8> {
+        zip(&[
+            ("demo/pom.xml", "<project/>"),
+            ("demo/src/main/resources/application.properties", "new"),
+        ])
+    }
+
+    #[test]
+    fn test_extract_into_new_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("app");
+
+        extract(&project(), &target, &options(OnConflict::Fail, None)).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(target.join("demo/pom.xml")).unwrap(),
+            "<project/>"
+        );
+    }
+
+    #[test]
+    fn test_strips_root_named_like_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("demo");
+
+        extract(&project(), &target, &options(OnConflict::Fail, None)).unwrap();
+
+        assert!(target.join("pom.xml").exists());
+        assert!(!target.join("demo").exists());
+    }
+
+    #[test]
+    fn test_strip_components() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("app");
+
+        extract(&project(), &target, &options(OnConflict::Fail, Some(1))).unwrap();
+        assert!(target.join("pom.xml").exists());
+
+        let target = dir.path().join("demo");
+        extract(&project(), &target, &options(OnConflict::Fail, Some(0))).unwrap();
+        assert!(target.join("demo/pom.xml").exists());
+
+        let target = dir.path().join("other");
+        let err = extract(&project(), &target, &options(OnConflict::Fail, Some(2))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stripping 2 path components leaves nothing of 'demo/pom.xml', pass a smaller \
+             --strip-components"
+        );
+        assert_eq!(CliError::exit_code_of(&err), 2);
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_rejects_entries_outside_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("demo");
+
+        let err = extract(
+            &zip(&[("../evil.sh", "rm -rf")]),
+            &target,
+            &options(OnConflict::Overwrite, None),
+        )
+        .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .starts_with("refusing to extract '../evil.sh'"));
+        assert_eq!(CliError::exit_code_of(&err), 2);
+        assert!(!dir.path().join("evil.sh").exists());
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let properties = dir
+            .path()
+            .join("demo/src/main/resources/application.properties");
+        fs::create_dir_all(properties.parent().unwrap()).unwrap();
+        fs::write(&properties, "old").unwrap();
+
+        let err = extract(&project(), dir.path(), &options(OnConflict::Fail, None)).unwrap_err();
+        assert!(err.to_string().ends_with("application.properties"));
+        assert_eq!(CliError::exit_code_of(&err), 2);
+        assert!(!dir.path().join("demo/pom.xml").exists());
+
+        extract(&project(), dir.path(), &options(OnConflict::Skip, None)).unwrap();
+        assert_eq!(fs::read_to_string(&properties).unwrap(), "old");
+        assert!(dir.path().join("demo/pom.xml").exists());
+
+        extract(
+            &project(),
+            dir.path(),
+            &options(OnConflict::Overwrite, None),
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(&properties).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_fails_on_non_empty_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("notes.txt"), "keep me").unwrap();
+
+        let err = extract(&project(), dir.path(), &options(OnConflict::Fail, None)).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .ends_with("pass --on-conflict to extract into it anyway"));
+    }
+
+    #[test]
+    fn test_write_file_conflict_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let pom = dir.path().join("pom.xml");
+
+        write_file(&pom, b"<project/>", OnConflict::Fail).unwrap();
+        assert_eq!(fs::read_to_string(&pom).unwrap(), "<project/>");
+
+        let err = write_file(&pom, b"new", OnConflict::Fail).unwrap_err();
+        assert_eq!(CliError::exit_code_of(&err), 2);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{} already exists, pass --on-conflict to choose what to do with it",
+                pom.display()
+            )
+        );
+        write_file(&pom, b"new", OnConflict::Skip).unwrap();
+        assert_eq!(fs::read_to_string(&pom).unwrap(), "<project/>");
+
+        write_file(&pom, b"new", OnConflict::Overwrite).unwrap();
+        assert_eq!(fs::read_to_string(&pom).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_print_tree() {
+        let archive = zip(&[
+            ("demo/pom.xml", &"x".repeat(1536)),
+            ("demo/src/main/java/Demo.java", "class Demo {}"),
+            ("demo/HELP.md", ""),
+        ]);
+
+        let mut out = Vec::new();
+        print_tree(&archive, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "demo/\n  \
+               HELP.md (0 B)\n  \
+               pom.xml (1.5 KiB)\n  \
+               src/\n    \
+                 main/\n      \
+                   java/\n        \
+                     Demo.java (13 B)\n\
+             3 files, 1.5 KiB\n"
+        );
+
+        let evil = zip(&[("../evil.sh", "rm -rf")]);
+        let err = print_tree(&evil, &mut Vec::new()).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("refusing to extract '../evil.sh'"));
+    }
+
+    #[test]
+    fn test_print_plan_matches_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("demo");
+        let properties = target.join("src/main/resources/application.properties");
+        fs::create_dir_all(properties.parent().unwrap()).unwrap();
+        fs::write(&properties, "old").unwrap();
+
+        let mut out = Vec::new();
+        print_plan(
+            &project(),
+            &target,
+            &options(OnConflict::Fail, None),
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "pom.xml (10 B)\n\
+                 src/\n  \
+                   main/\n    \
+                     resources/\n      \
+                       application.properties (3 B)\n\
+                 2 files, 13 B\n\
+                 would conflict with:\n  \
+                   {}\n",
+                properties.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_plan_in_merge_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), ".env\n").unwrap();
+        let archive = zip(&[
+            ("demo/pom.xml", "<project/>"),
+            ("demo/.gitignore", "target/\n"),
+        ]);
+        let init = Extraction {
+            merge: true,
+            ..options(OnConflict::Fail, None)
+        };
+
+        let mut out = Vec::new();
+        print_plan(&archive, dir.path(), &init, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                ".gitignore (8 B)\n\
+                 pom.xml (10 B)\n\
+                 2 files, 18 B\n\
+                 would merge:\n  \
+                   {}\n",
+                dir.path().join(".gitignore").display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_gitignore() {
+        assert_eq!(
+            merge_gitignore(
+                "target/\n.idea\n",
+                "HELP.md\ntarget/\n\n### IntelliJ IDEA ###\n.idea\n*.iml\n\n"
+            ),
+            "target/\n.idea\n\nHELP.md\n\n### IntelliJ IDEA ###\n*.iml\n"
+        );
+        assert_eq!(merge_gitignore("target/", "target/\n"), "target/");
+        assert_eq!(merge_gitignore("", "target/\n"), "target/\n");
+    }
+
+    #[test]
+    fn test_merge_into_existing_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("README.md"), "# Billing").unwrap();
+        fs::write(dir.path().join(".gitignore"), ".env\n").unwrap();
+        let zip = zip(&[
+            ("demo/pom.xml", "<project/>"),
+            ("demo/.gitignore", "target/\n.env\n"),
+        ]);
+        let init = Extraction {
+            merge: true,
+            ..options(OnConflict::Fail, None)
+        };
+
+        extract(&zip, dir.path(), &init).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("README.md")).unwrap(),
+            "# Billing"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+            ".env\n\ntarget/\n"
+        );
+        assert!(dir.path().join("pom.xml").exists());
+
+        let err = extract(&zip, dir.path(), &init).unwrap_err();
+        assert!(err.to_string().ends_with("pom.xml"));
+    }
+
+    #[test]
+    fn test_file_mode() {
+        assert_eq!(
+            file_mode(Path::new("demo/pom.xml"), Some(0o100644)),
+            Some(0o644)
+        );
+        assert_eq!(file_mode(Path::new("demo/pom.xml"), None), None);
+        assert_eq!(
+            file_mode(Path::new("demo/mvnw"), Some(0o100644)),
+            Some(0o755)
+        );
+        assert_eq!(file_mode(Path::new("demo/gradlew"), Some(0)), Some(0o755));
+        assert_eq!(file_mode(Path::new("demo/gradlew"), None), Some(0o755));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_preserves_unix_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, mode) in [
+            ("demo/mvnw", 0o755),
+            ("demo/gradlew", 0o644),
+            ("demo/secret.properties", 0o600),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default().unix_permissions(mode))
+                .unwrap();
+            writer.write_all(b"#!/bin/sh").unwrap();
+        }
+        let zip = writer.finish().unwrap().into_inner();
+        let dir = tempfile::tempdir().unwrap();
+
+        extract(&zip, dir.path(), &options(OnConflict::Fail, None)).unwrap();
+
+        let mode = |name: &str| {
+            fs::metadata(dir.path().join("demo").join(name))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777
+        };
+        assert_eq!(mode("mvnw"), 0o755);
+        assert_eq!(mode("gradlew"), 0o755);
+        assert_eq!(mode("secret.properties"), 0o600);
+    }
+}
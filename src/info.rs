@@ -0,0 +1,218 @@
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+
+use crate::{
+    error::CliError,
+    metadata::InitializrMetadata,
+    steps::{Step, StepKind},
+    validate,
+    version::{Version, VersionRange},
+};
+
+/// Expands the `{bootVersion}` placeholder of templated links.
+fn expand_href(href: &str, templated: bool, boot_version: &str) -> String {
+    if templated {
+        href.replace("{bootVersion}", boot_version)
+    } else {
+        href.to_owned()
+    }
+}
+
+pub(crate) fn print_info(
+    metadata: &InitializrMetadata,
+    steps: &[Step],
+    id: &str,
+    boot_version: Option<&str>,
+    out: &mut impl Write,
+) -> Result<()> {
+    let Some((group, dep)) = metadata.dependency(id) else {
+        let invalid = steps
+            .iter()
+            .find(|step| matches!(step.kind, StepKind::MultiSelect { .. }))
+            .map(|step| validate::check_value(step, id))
+            .unwrap_or_default();
+        match invalid.first() {
+            Some(invalid) => return Err(CliError::Invalid(invalid.to_string()).into()),
+            None => bail!("the server does not offer any dependencies"),
+        }
+    };
+    let boot_version = match boot_version {
+        Some(v) => v.to_owned(),
+        None => steps
+            .iter()
+            .find_map(|step| match &step.kind {
+                StepKind::SingleSelect { default, .. } if step.name == "bootVersion" => {
+                    Some(default.clone())
+                }
+                _ => None,
+            })
+            .context("the server does not offer a default boot version, pass --boot-version")?,
+    };
+
+    writeln!(out, "{} ({})", dep.name, dep.id)?;
+    if let Some(description) = &dep.description {
+        writeln!(out, "{}", description)?;
+    }
+    writeln!(out)?;
+
+    let mut field =
+        |name: &str, value: &str| writeln!(out, "{:<15}{}", format!("{}:", name), value);
+    field("Group", &group.name)?;
+    if let (Some(group_id), Some(artifact_id)) = (&dep.group_id, &dep.artifact_id) {
+        let coordinates = match &dep.version {
+            Some(version) => format!("{}:{}:{}", group_id, artifact_id, version),
+            None => format!("{}:{}", group_id, artifact_id),
+        };
+        field("Coordinates", &coordinates)?;
+    }
+    if let Some(scope) = &dep.scope {
+        field("Scope", scope)?;
+    }
+    if let Some(starter) = dep.starter {
+        field("Starter", if starter { "yes" } else { "no" })?;
+    }
+    if let Some(bom) = &dep.bom {
+        field("BOM", bom)?;
+    }
+    if let Some(repository) = &dep.repository {
+        field("Repository", repository)?;
+    }
+    if let Some(range) = &dep.compatibility_range {
+        let compatible = range
+            .parse::<VersionRange>()
+            .ok()
+            .zip(boot_version.parse::<Version>().ok())
+            .map(|(range, version)| range.contains(&version));
+        let value = match compatible {
+            Some(true) => format!("{} (compatible with {})", range, boot_version),
+            Some(false) => format!("{} (not compatible with {})", range, boot_version),
+            None => range.clone(),
+        };
+        field("Compatibility", &value)?;
+    }
+    if !dep.facets.is_empty() {
+        field("Facets", &dep.facets.join(", "))?;
+    }
+
+    if !dep.links.is_empty() {
+        writeln!(out, "\nLinks:")?;
+        for link in &dep.links {
+            let href = expand_href(&link.href, link.templated, &boot_version);
+            match &link.description {
+                Some(description) => writeln!(out, "  {:<11}{} - {}", link.rel, description, href)?,
+                None => writeln!(out, "  {:<11}{}", link.rel, href)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn metadata() -> (InitializrMetadata, Vec<Step>) {
+        let json = json!({
+            "dependencies": {
+                "id": "dependencies",
+                "type": "HIERARCHICAL_MULTI_SELECT",
+                "content": [
+                    {
+                        "name": "Web",
+                        "content": [
+                            {
+                                "name": "Spring Web",
+                                "id": "web",
+                                "facets": ["web", "json"],
+                                "groupId": "org.springframework.boot",
+                                "artifactId": "spring-boot-starter-web",
+                                "scope": "compile",
+                                "description": "Build web, including RESTful, applications using Spring MVC.",
+                                "starter": true,
+                                "links": [
+                                    {
+                                        "rel": "guide",
+                                        "href": "https://spring.io/guides/gs/rest-service/",
+                                        "description": "Building a RESTful Web Service"
+                                    },
+                                    {
+                                        "rel": "reference",
+                                        "href": "https://docs.spring.io/spring-boot/{bootVersion}/reference/web/servlet.html",
+                                        "templated": true
+                                    }
+                                ]
+                            },
+                            {
+                                "name": "Spring Modulith",
+                                "id": "modulith",
+                                "groupId": "org.springframework.modulith",
+                                "artifactId": "spring-modulith-starter-core",
+                                "compatibilityRange": "[3.3.0,3.5.0-M1)",
+                                "bom": "spring-modulith"
+                            }
+                        ]
+                    }
+                ]
+            },
+            "bootVersions": {
+                "id": "bootVersion",
+                "type": "SINGLE_SELECT",
+                "content": [
+                    { "name": "3.5.0 (M1)", "id": "3.5.0-M1", "default": false },
+                    { "name": "3.4.2", "id": "3.4.2", "default": true }
+                ]
+            }
+        });
+        let (metadata, _) = InitializrMetadata::from_json(json).unwrap();
+        let steps = Step::from_metadata(&metadata);
+        (metadata, steps)
+    }
+
+    fn info(id: &str, boot_version: Option<&str>) -> Result<String> {
+        let (metadata, steps) = metadata();
+        let mut out = Vec::new();
+        print_info(&metadata, &steps, id, boot_version, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_info_expands_links() {
+        assert_eq!(
+            info("web", None).unwrap(),
+            "Spring Web (web)\n\
+             Build web, including RESTful, applications using Spring MVC.\n\
+             \n\
+             Group:         Web\n\
+             Coordinates:   org.springframework.boot:spring-boot-starter-web\n\
+             Scope:         compile\n\
+             Starter:       yes\n\
+             Facets:        web, json\n\
+             \n\
+             Links:\n  \
+               guide      Building a RESTful Web Service - https://spring.io/guides/gs/rest-service/\n  \
+               reference  https://docs.spring.io/spring-boot/3.4.2/reference/web/servlet.html\n"
+        );
+        assert!(info("web", Some("3.5.0-M1"))
+            .unwrap()
+            .contains("https://docs.spring.io/spring-boot/3.5.0-M1/reference/web/servlet.html"));
+    }
+
+    #[test]
+    fn test_info_compatibility() {
+        assert!(info("modulith", None)
+            .unwrap()
+            .contains("Compatibility: [3.3.0,3.5.0-M1) (compatible with 3.4.2)\n"));
+        assert!(info("modulith", Some("3.5.0-M1"))
+            .unwrap()
+            .contains("Compatibility: [3.3.0,3.5.0-M1) (not compatible with 3.5.0-M1)\n"));
+    }
+
+    #[test]
+    fn test_info_unknown_dependency() {
+        assert_eq!(
+            info("wbe", None).unwrap_err().to_string(),
+            "invalid dependencies 'wbe', did you mean web?"
+        );
+    }
+}
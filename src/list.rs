@@ -0,0 +1,262 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::Write;
+
+use crate::{
+    args::{ListKind, OutputFormat},
+    steps::{ItemKind, Step, StepKind},
+};
+
+impl ListKind {
+    pub(crate) fn step_id(self) -> &'static str {
+        match self {
+            ListKind::Deps => "dependencies",
+            ListKind::BootVersions => "bootVersion",
+            ListKind::JavaVersions => "javaVersion",
+            ListKind::Types => "type",
+            ListKind::Languages => "language",
+            ListKind::Packagings => "packaging",
+        }
+    }
+}
+
+/// Rows keyed by column, the first entry of each pair being the json key and
+/// the second the table header.
+pub(crate) struct Table {
+    pub(crate) columns: Vec<(&'static str, &'static str)>,
+    pub(crate) rows: Vec<Vec<Value>>,
+}
+
+fn table_for(step: &Step) -> Table {
+    match &step.kind {
+        StepKind::Text { default } => Table {
+            columns: vec![("default", "DEFAULT")],
+            rows: vec![vec![json!(default)]],
+        },
+        StepKind::SingleSelect { default, values } => Table {
+            columns: vec![("id", "ID"), ("name", "NAME"), ("default", "DEFAULT")],
+            rows: values
+                .iter()
+                .map(|item| vec![json!(item.id), json!(item.name), json!(&item.id == default)])
+                .collect(),
+        },
+        StepKind::Action { default, values } => Table {
+            columns: vec![
+                ("id", "ID"),
+                ("name", "NAME"),
+                ("action", "ACTION"),
+                ("default", "DEFAULT"),
+            ],
+            rows: values
+                .iter()
+                .map(|item| {
+                    let action = match &item.kind {
+                        ItemKind::Action(action) => json!(action),
+                        _ => Value::Null,
+                    };
+                    vec![
+                        json!(item.id),
+                        json!(item.name),
+                        action,
+                        json!(&item.id == default),
+                    ]
+                })
+                .collect(),
+        },
+        StepKind::MultiSelect { values } => Table {
+            columns: vec![
+                ("id", "ID"),
+                ("name", "NAME"),
+                ("group", "GROUP"),
+                ("compatibilityRange", "COMPATIBILITY"),
+            ],
+            rows: values
+                .iter()
+                .map(|item| {
+                    let group = match &item.kind {
+                        ItemKind::Dependency(group) => json!(group),
+                        _ => Value::Null,
+                    };
+                    let range = item
+                        .compatibility_range
+                        .as_ref()
+                        .map_or(Value::Null, |range| json!(range));
+                    vec![json!(item.id), json!(item.name), group, range]
+                })
+                .collect(),
+        },
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Bool(true) => "*".to_owned(),
+        Value::Bool(false) | Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn write_table(table: &Table, out: &mut impl Write) -> Result<()> {
+    let cells: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| row.iter().map(cell).collect())
+        .collect();
+    let widths: Vec<usize> = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, (_, header))| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain([header.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let headers: Vec<String> = table.columns.iter().map(|(_, h)| h.to_string()).collect();
+    for row in [headers].iter().chain(cells.iter()) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+pub(crate) fn print_list(
+    steps: &[Step],
+    kind: ListKind,
+    format: OutputFormat,
+    out: &mut impl Write,
+) -> Result<()> {
+    let step = steps
+        .iter()
+        .find(|step| step.name == kind.step_id())
+        .with_context(|| format!("the server does not offer any {}", kind.step_id()))?;
+    table_for(step).print(format, out)
+}
+
+impl Table {
+    pub(crate) fn print(&self, format: OutputFormat, out: &mut impl Write) -> Result<()> {
+        match format {
+            OutputFormat::Table => write_table(self, out)?,
+            OutputFormat::Json => {
+                let rows: Vec<Value> = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        Value::Object(
+                            self.columns
+                                .iter()
+                                .zip(row)
+                                .map(|((key, _), value)| (key.to_string(), value.clone()))
+                                .collect(),
+                        )
+                    })
+                    .collect();
+                serde_json::to_writer_pretty(&mut *out, &rows)?;
+                writeln!(out)?;
+            }
+            OutputFormat::Plain => {
+                for row in &self.rows {
+                    writeln!(out, "{}", cell(&row[0]))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::steps::Item;
+
+    fn steps() -> Vec<Step> {
+        vec![
+            Step {
+                name: "javaVersion".to_owned(),
+                kind: StepKind::SingleSelect {
+                    default: "17".to_owned(),
+                    values: vec![
+                        Item::new_default("21".to_owned(), "21".to_owned()),
+                        Item::new_default("17".to_owned(), "17".to_owned()),
+                    ],
+                },
+            },
+            Step {
+                name: "dependencies".to_owned(),
+                kind: StepKind::MultiSelect {
+                    values: vec![
+                        Item::new_dependency(
+                            "modulith".to_owned(),
+                            "Spring Modulith".to_owned(),
+                            "Developer Tools".to_owned(),
+                        )
+                        .with_compatibility_range(Some("[3.3.0,3.5.0-M1)".to_owned())),
+                        Item::new_dependency(
+                            "web".to_owned(),
+                            "Spring Web".to_owned(),
+                            "Web".to_owned(),
+                        )
+                        .with_compatibility_range(Some("3.3.0".to_owned())),
+                    ],
+                },
+            },
+        ]
+    }
+
+    fn list(kind: ListKind, format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        print_list(&steps(), kind, format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(
+            list(ListKind::Deps, OutputFormat::Table),
+            "ID        NAME             GROUP            COMPATIBILITY\n\
+             modulith  Spring Modulith  Developer Tools  [3.3.0,3.5.0-M1)\n\
+             web       Spring Web       Web              3.3.0\n"
+        );
+        assert_eq!(
+            list(ListKind::JavaVersions, OutputFormat::Table),
+            "ID  NAME  DEFAULT\n\
+             21  21\n\
+             17  17    *\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json: Value =
+            serde_json::from_str(&list(ListKind::JavaVersions, OutputFormat::Json)).unwrap();
+        assert_eq!(
+            json,
+            json!([
+                { "id": "21", "name": "21", "default": false },
+                { "id": "17", "name": "17", "default": true }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(list(ListKind::Deps, OutputFormat::Plain), "modulith\nweb\n");
+    }
+
+    #[test]
+    fn test_missing_step() {
+        let mut out = Vec::new();
+        let err = print_list(&steps(), ListKind::Types, OutputFormat::Plain, &mut out).unwrap_err();
+        assert_eq!(err.to_string(), "the server does not offer any type");
+    }
+}
@@ -1,58 +1,263 @@
-use std::{collections::HashMap, fs, io::Write};
+use std::{collections::HashMap, io::Write, path::Path, process::ExitCode};
 
-use anyhow::{Context, Ok, Result};
-use args::Args;
+use anyhow::{bail, Context, Result};
+use args::{Args, Command, GenerateArgs};
+use cache::MetadataCache;
 use clap::Parser;
-use request::{get_zip, ResponseStep};
+use config::{Config, Profile};
+use error::CliError;
+use extract::Extraction;
+use metadata::InitializrMetadata;
+use request::get_zip;
 use resolve_path::PathResolveExt;
 use std::io::Cursor;
-use steps::Step;
+use steps::{Step, StepKind};
 use zip::ZipArchive;
 
 mod args;
+mod cache;
+mod config;
+mod error;
+mod extract;
+mod info;
+mod list;
+mod metadata;
+mod picker;
+mod print_url;
 mod request;
+mod search;
 mod steps;
 mod user_innput;
+mod validate;
+mod version;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let args = Args::parse();
-    let json = request::get_options(&args.url)?;
-
-    let cli_values = build_cli_values_map(&args);
-    let steps = Step::from_json(json)?;
-
-    let responses = steps
-        .into_iter()
-        .map(|step| {
-            let prefill = cli_values.get(&step.name).map(|s| s.as_str());
-            if args.non_interactive && prefill.is_none() {
-                // Use defaults in non-interactive mode
-                let default_response = match &step.kind {
-                    steps::StepKind::Text { default } => default.clone(),
-                    steps::StepKind::SingleSelect { default, .. } => default.clone(),
-                    steps::StepKind::Action { default, .. } => default.clone(),
-                    steps::StepKind::MultiSelect { .. } => String::new(),
-                };
-                Ok(ResponseStep {
-                    step: step.to_owned(),
-                    response: default_response,
-                })
-            } else {
-                user_innput::get_user_input(&step, prefill)
-            }
-        })
-        .collect::<Result<Vec<ResponseStep>>>()?;
-
-    let (file_name, zip) = get_zip(&args.url, &responses)?;
-    let path = args
-        .path
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::from(CliError::exit_code_of(&err))
+        }
+    }
+}
+
+fn run(args: Args) -> Result<()> {
+    let cache = MetadataCache::default_location();
+    let json = request::get_options(&args.url, cache.as_ref(), args.cache_mode())?;
+
+    let (metadata, errors) = InitializrMetadata::from_json(json)?;
+    for err in errors {
+        eprintln!("warning: ignoring malformed metadata, {}", err);
+    }
+    let steps = Step::from_metadata(&metadata);
+
+    match args.command {
+        Some(Command::List { what, format }) => {
+            list::print_list(&steps, what, format, &mut std::io::stdout())
+        }
+        Some(Command::Search {
+            query,
+            limit,
+            format,
+        }) => search::print_search(
+            &steps,
+            &query.join(" "),
+            limit,
+            format,
+            &mut std::io::stdout(),
+        ),
+        Some(Command::Info {
+            dependency,
+            boot_version,
+        }) => info::print_info(
+            &metadata,
+            &steps,
+            &dependency,
+            boot_version.as_deref(),
+            &mut std::io::stdout(),
+        ),
+        Some(Command::Init { generate: init }) => generate(&args.url, &init, steps, true),
+        None => generate(&args.url, &args.generate, steps, false),
+    }
+}
+
+fn generate(url: &str, args: &GenerateArgs, mut steps: Vec<Step>, init: bool) -> Result<()> {
+    if init && args.path.is_some() {
+        return Err(CliError::Invalid(
+            "init always generates into the current directory, drop --path".to_owned(),
+        )
+        .into());
+    }
+    validate::validate_step_ids(
+        &steps,
+        args.set
+            .iter()
+            .map(|(id, _)| id)
+            .chain(&args.step_order)
+            .map(String::as_str),
+    )?;
+    let config = Config::load()?;
+
+    if !args.step_order.is_empty() {
+        Step::sort_by_order(&mut steps, &args.step_order);
+    } else if let Some(order) = &config.step_order {
+        validate::validate_step_ids(&steps, order.iter().map(String::as_str))
+            .context("invalid step-order in the config")?;
+        Step::sort_by_order(&mut steps, order);
+    } else {
+        Step::sort_by_order(&mut steps, steps::DEFAULT_STEP_ORDER);
+    }
+
+    // Dedicated flags take precedence over `--set`, environment variables, the
+    // profile and then the server defaults
+    let profile = args
+        .profile
         .as_deref()
-        .or(file_name.as_deref())
-        .unwrap_or("./spring-app.zip");
-    write_zip(path, zip)
+        .map(|name| config.profile(name))
+        .transpose()?;
+    if let (Some(name), Some(profile)) = (&args.profile, profile) {
+        validate::validate_step_ids(&steps, profile.values.keys().map(String::as_str))
+            .with_context(|| format!("invalid key in profile '{}'", name))?;
+    }
+    let mut prefills = profile.map(Profile::prefills).unwrap_or_default();
+    prefills.extend(config::env_values(
+        steps.iter().map(|step| step.name.as_str()),
+        |name| std::env::var(name).ok(),
+    ));
+    prefills.extend(args.set.iter().cloned());
+    prefills.extend(build_cli_values_map(args));
+    validate::validate_prefills(&steps, &prefills)?;
+
+    let seeds = seeds(
+        &steps,
+        &mut prefills,
+        args.seed_dependencies.as_deref(),
+        args.profile.as_deref().zip(profile),
+    )?;
+    validate::validate_prefills(&steps, &seeds)?;
+
+    let mut responses =
+        user_innput::get_user_inputs(steps, &prefills, &seeds, args.non_interactive)?;
+
+    if !args.non_interactive && !user_innput::review(&mut responses)? {
+        return Err(CliError::Aborted("aborted, no project was generated".to_owned()).into());
+    }
+
+    if args.print_url {
+        return print_url::print_url(url, &responses, &mut std::io::stdout());
+    }
+
+    let (file_name, zip) = get_zip(url, &responses)?;
+    let path = if init {
+        std::env::current_dir()?
+    } else {
+        args.path
+            .as_deref()
+            .or(file_name.as_deref())
+            .unwrap_or("./spring-app.zip")
+            .try_resolve()?
+            .into_owned()
+    };
+    let is_zip = ZipArchive::new(Cursor::new(&zip)).is_ok();
+    if init && !is_zip {
+        bail!("init needs a project archive, pick a project type rather than a build file");
+    }
+    // Archives are extracted unless written to a path with an extension like `demo.zip`
+    let extract_zip = is_zip && (init || path.extension().is_none());
+    if !extract_zip && args.strip_components.is_some() {
+        return Err(CliError::Invalid(format!(
+            "--strip-components only applies when extracting an archive, {} is written as is",
+            path.display()
+        ))
+        .into());
+    }
+    let extraction = extract_zip.then_some(Extraction {
+        on_conflict: args.on_conflict,
+        strip_components: args.strip_components,
+        merge: init,
+    });
+    if args.dry_run {
+        return dry_run(&path, &zip, extraction.as_ref());
+    }
+    match &extraction {
+        Some(extraction) => extract::extract(&zip, &path, extraction),
+        None => extract::write_file(&path, &zip, args.on_conflict),
+    }
+    .map_err(|err| match err.downcast_ref::<CliError>() {
+        // Refusals and aborts keep their own exit code, the rest failed to write
+        Some(_) => err,
+        None => CliError::Write(err).into(),
+    })
+}
+
+/// The values checked when the multi-select pickers open. `--seed-dependencies`
+/// replaces the values prefilled by `--set`, the environment or the profile,
+/// while a profile seed gives way to them and cannot be combined with the
+/// same step in the profile.
+fn seeds(
+    steps: &[Step],
+    prefills: &mut HashMap<String, String>,
+    flag: Option<&str>,
+    profile: Option<(&str, &Profile)>,
+) -> Result<HashMap<String, String>> {
+    let multi_selects = steps
+        .iter()
+        .filter(|step| matches!(step.kind, StepKind::MultiSelect { .. }));
+    if let Some(seed) = flag {
+        return Ok(multi_selects
+            .map(|step| {
+                prefills.remove(&step.name);
+                (step.name.clone(), seed.to_owned())
+            })
+            .collect());
+    }
+    let Some((name, profile, seed)) =
+        profile.and_then(|(name, profile)| Some((name, profile, profile.seed_dependencies()?)))
+    else {
+        return Ok(HashMap::new());
+    };
+    let both: Vec<&str> = multi_selects
+        .clone()
+        .filter(|step| profile.values.contains_key(&step.name))
+        .map(|step| step.name.as_str())
+        .collect();
+    if !both.is_empty() {
+        return Err(CliError::Invalid(format!(
+            "profile '{}' sets both {} and seed-dependencies, drop one of them",
+            name,
+            both.join(", ")
+        ))
+        .into());
+    }
+    Ok(multi_selects
+        .filter(|step| !prefills.contains_key(&step.name))
+        .map(|step| (step.name.clone(), seed.clone()))
+        .collect())
 }
 
-fn build_cli_values_map(args: &Args) -> HashMap<String, String> {
+/// Prints what would be written: the files extraction would write, the
+/// archive entries or the file content.
+fn dry_run(path: &Path, zip: &[u8], extraction: Option<&Extraction>) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    if let Some(extraction) = extraction {
+        println!("would extract to {}", path.display());
+        return extract::print_plan(zip, path, extraction, &mut stdout);
+    }
+    if path.exists() {
+        println!("{} already exists", path.display());
+    }
+    if ZipArchive::new(Cursor::new(zip)).is_ok() {
+        println!("would write the archive to {}", path.display());
+        extract::print_tree(zip, &mut stdout)
+    } else {
+        println!("would write to {}", path.display());
+        stdout.write_all(zip)?;
+        Ok(())
+    }
+}
+
+fn build_cli_values_map(args: &GenerateArgs) -> HashMap<String, String> {
     let mut map = HashMap::new();
 
     if let Some(v) = &args.language {
@@ -95,34 +300,23 @@ fn build_cli_values_map(args: &Args) -> HashMap<String, String> {
     map
 }
 
-fn write_zip(file_name: &str, zip: Vec<u8>) -> Result<()> {
-    let path = file_name.try_resolve()?;
-    if path.extension().is_none() && ZipArchive::new(Cursor::new(&zip)).is_ok() {
-        fs::create_dir_all(&path)?;
-        println!("writing data to {}", path.display());
-        let mut archive = ZipArchive::new(Cursor::new(&zip))?;
-        archive.extract(&path)?;
-    } else {
-        let parent = &path.parent().context("dident find parent of file")?;
-        fs::create_dir_all(parent)?;
-        println!("writing data to {}", path.display());
-        let mut file = fs::File::create(path)?;
-        file.write_all(&zip)?;
-    }
-    Ok(())
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::steps::{Item, StepKind};
+    use crate::{args::OnConflict, steps::Item};
 
     #[test]
     fn test_build_cli_values_map_empty() {
-        let args = Args {
-            url: "https://start.spring.io".to_string(),
+        let args = GenerateArgs {
             path: None,
+            on_conflict: OnConflict::Fail,
+            strip_components: None,
             non_interactive: false,
+            print_url: false,
+            dry_run: false,
+            profile: None,
+            set: Vec::new(),
+            step_order: Vec::new(),
             language: None,
             boot_version: None,
             group_id: None,
@@ -133,6 +327,7 @@ mod test {
             packaging: None,
             java_version: None,
             dependencies: None,
+            seed_dependencies: None,
             project_type: None,
             project_version: None,
         };
@@ -143,10 +338,16 @@ mod test {
 
     #[test]
     fn test_build_cli_values_map_with_values() {
-        let args = Args {
-            url: "https://start.spring.io".to_string(),
+        let args = GenerateArgs {
             path: None,
+            on_conflict: OnConflict::Fail,
+            strip_components: None,
             non_interactive: false,
+            print_url: false,
+            dry_run: false,
+            profile: None,
+            set: Vec::new(),
+            step_order: Vec::new(),
             language: Some("java".to_string()),
             boot_version: Some("3.4.2".to_string()),
             group_id: Some("com.example".to_string()),
@@ -157,6 +358,7 @@ mod test {
             packaging: Some("jar".to_string()),
             java_version: Some("21".to_string()),
             dependencies: Some("web,data-jpa".to_string()),
+            seed_dependencies: None,
             project_type: Some("maven-project".to_string()),
             project_version: Some("1.0.0".to_string()),
         };
@@ -269,6 +471,58 @@ mod test {
         assert_eq!(response, "");
     }
 
+    #[test]
+    fn test_seeds_follow_precedence() {
+        let steps = vec![crate::steps::Step {
+            name: "dependencies".to_string(),
+            kind: StepKind::MultiSelect {
+                values: vec![Item::new_dependency(
+                    "web".to_string(),
+                    "Spring Web".to_string(),
+                    "Web".to_string(),
+                )],
+            },
+        }];
+        let seeding: Profile = toml::from_str(r#"seed-dependencies = ["web"]"#).unwrap();
+        let prefilled = || -> HashMap<String, String> {
+            [("dependencies".to_string(), "actuator".to_string())]
+                .into_iter()
+                .collect()
+        };
+
+        // The flag replaces lower-precedence values
+        let mut prefills = prefilled();
+        let seeded = seeds(&steps, &mut prefills, Some("web"), Some(("team", &seeding))).unwrap();
+        assert_eq!(seeded["dependencies"], "web");
+        assert!(prefills.is_empty());
+
+        // Values from --set or the environment win over the profile seed
+        let mut prefills = prefilled();
+        let seeded = seeds(&steps, &mut prefills, None, Some(("team", &seeding))).unwrap();
+        assert!(seeded.is_empty());
+        assert_eq!(prefills["dependencies"], "actuator");
+
+        let seeded = seeds(&steps, &mut HashMap::new(), None, Some(("team", &seeding))).unwrap();
+        assert_eq!(seeded["dependencies"], "web");
+        assert!(seeds(&steps, &mut prefilled(), None, None)
+            .unwrap()
+            .is_empty());
+
+        let both: Profile = toml::from_str(
+            r#"
+            seed-dependencies = ["web"]
+            dependencies = ["actuator"]
+            "#,
+        )
+        .unwrap();
+        let err = seeds(&steps, &mut prefilled(), None, Some(("team", &both))).unwrap_err();
+        assert_eq!(CliError::exit_code_of(&err), 2);
+        assert_eq!(
+            err.to_string(),
+            "profile 'team' sets both dependencies and seed-dependencies, drop one of them"
+        );
+    }
+
     #[test]
     fn test_prefill_overrides_default() {
         let step = crate::steps::Step {
@@ -0,0 +1,811 @@
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt};
+
+use crate::version::VersionRange;
+
+/// The metadata served by an Initializr instance on `/metadata/config`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InitializrMetadata {
+    pub configuration: Option<Configuration>,
+    /// The capabilities sorted by their key in the metadata, as `serde_json`
+    /// does not preserve the order the server sent them in.
+    pub capabilities: Vec<Capability>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Capability {
+    Text(TextCapability),
+    SingleSelect(SingleSelectCapability),
+    Action(ActionCapability),
+    Dependencies(DependenciesCapability),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TextCapability {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingleSelectCapability {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content: Vec<SelectOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SelectOption {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionCapability {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content: Vec<ProjectType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProjectType {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub action: String,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependenciesCapability {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub content: Vec<DependencyGroup>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyGroup {
+    pub name: String,
+    pub content: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependency {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub group_id: Option<String>,
+    pub artifact_id: Option<String>,
+    pub version: Option<String>,
+    pub scope: Option<String>,
+    pub starter: Option<bool>,
+    pub bom: Option<String>,
+    pub repository: Option<String>,
+    pub compatibility_range: Option<String>,
+    #[serde(default)]
+    pub facets: Vec<String>,
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Link {
+    pub rel: String,
+    pub href: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub templated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Configuration {
+    pub env: Option<Env>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Env {
+    pub artifact_repository: Option<String>,
+    pub spring_boot_metadata_url: Option<String>,
+    pub google_analytics_tracking_code: Option<String>,
+    pub fallback_application_name: Option<String>,
+    pub kotlin: Option<KotlinEnv>,
+    pub maven: Option<MavenEnv>,
+    pub platform: Option<PlatformEnv>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KotlinEnv {
+    pub default_version: Option<String>,
+    #[serde(default)]
+    pub mappings: Vec<KotlinMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KotlinMapping {
+    pub compatibility_range: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MavenEnv {
+    pub parent: Option<MavenParent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MavenParent {
+    pub group_id: Option<String>,
+    pub artifact_id: Option<String>,
+    pub version: Option<String>,
+    pub relative_path: Option<String>,
+    #[serde(default)]
+    pub include_spring_boot_bom: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformEnv {
+    pub compatibility_range: Option<String>,
+    pub v1_format_compatibility_range: Option<String>,
+    pub v2_format_compatibility_range: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataErrorKind {
+    MissingField(String),
+    Invalid(String),
+    NoDefault,
+    NoOptions,
+}
+
+/// A node of the Initializr metadata that could not be deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataError {
+    /// JSON pointer of the offending node, e.g. `/types/content/3/action`.
+    pub pointer: String,
+    /// Id of the step the node belongs to.
+    pub step: String,
+    pub kind: MetadataErrorKind,
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            MetadataErrorKind::MissingField(field) => write!(
+                f,
+                "step '{}': missing field '{}' at {}",
+                self.step, field, self.pointer
+            ),
+            MetadataErrorKind::Invalid(msg) => {
+                write!(f, "step '{}': {} at {}", self.step, msg, self.pointer)
+            }
+            MetadataErrorKind::NoDefault => write!(
+                f,
+                "step '{}': no option at {} is marked as default",
+                self.step, self.pointer
+            ),
+            MetadataErrorKind::NoOptions => {
+                write!(f, "step '{}': no options at {}", self.step, self.pointer)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MetadataError {}
+
+impl InitializrMetadata {
+    /// Deserializes the metadata, skipping malformed capabilities and options and
+    /// returning what went wrong alongside what could be used.
+    pub fn from_json(json: Value) -> Result<(Self, Vec<MetadataError>)> {
+        let json = json.as_object().context("json")?;
+
+        let mut metadata = InitializrMetadata::default();
+        let mut errors = Vec::new();
+
+        for (key, body) in json {
+            let node = Node {
+                value: body,
+                pointer: format!("/{}", escape_pointer(key)),
+                step: body["id"].as_str().unwrap_or(key),
+            };
+
+            if key == "configuration" {
+                match node.deserialize() {
+                    Ok(configuration) => metadata.configuration = Some(configuration),
+                    Err(err) => errors.push(err),
+                }
+                continue;
+            }
+
+            let Some(t) = body["type"].as_str() else {
+                continue;
+            };
+
+            match node.capability(t, &mut errors) {
+                Ok(Some(capability)) => metadata.capabilities.push(capability),
+                Ok(None) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+        Ok((metadata, errors))
+    }
+
+    pub fn dependency(&self, id: &str) -> Option<(&DependencyGroup, &Dependency)> {
+        self.capabilities
+            .iter()
+            .filter_map(|c| match c {
+                Capability::Dependencies(c) => Some(c),
+                _ => None,
+            })
+            .flat_map(|c| c.content.iter())
+            .find_map(|group| {
+                group
+                    .content
+                    .iter()
+                    .find(|dep| dep.id == id)
+                    .map(|dep| (group, dep))
+            })
+    }
+}
+
+#[derive(Deserialize)]
+struct Header {
+    id: String,
+    title: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GroupHeader {
+    name: String,
+}
+
+struct Node<'a> {
+    value: &'a Value,
+    pointer: String,
+    step: &'a str,
+}
+
+impl<'a> Node<'a> {
+    fn error(&self, kind: MetadataErrorKind) -> MetadataError {
+        MetadataError {
+            pointer: self.pointer.clone(),
+            step: self.step.to_owned(),
+            kind,
+        }
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self) -> Result<T, MetadataError> {
+        serde_path_to_error::deserialize(self.value).map_err(|err| {
+            let mut pointer = self.pointer.clone();
+            for segment in err.path().iter() {
+                match segment {
+                    serde_path_to_error::Segment::Seq { index } => {
+                        pointer.push_str(&format!("/{}", index))
+                    }
+                    serde_path_to_error::Segment::Map { key } => {
+                        pointer.push_str(&format!("/{}", escape_pointer(key)))
+                    }
+                    _ => {}
+                }
+            }
+            let msg = err.into_inner().to_string();
+            // serde reports a missing field on its parent, point at the field instead.
+            let kind = match msg
+                .strip_prefix("missing field `")
+                .and_then(|field| field.strip_suffix('`'))
+            {
+                Some(field) => {
+                    pointer.push_str(&format!("/{}", escape_pointer(field)));
+                    MetadataErrorKind::MissingField(field.to_owned())
+                }
+                None => MetadataErrorKind::Invalid(msg),
+            };
+            MetadataError {
+                pointer,
+                step: self.step.to_owned(),
+                kind,
+            }
+        })
+    }
+
+    fn content(&self) -> Result<Vec<Node<'a>>, MetadataError> {
+        let node = Node {
+            value: self.value.get("content").unwrap_or(&Value::Null),
+            pointer: format!("{}/content", self.pointer),
+            step: self.step,
+        };
+        let values = match node.value {
+            Value::Array(values) => values,
+            Value::Null => {
+                return Err(node.error(MetadataErrorKind::MissingField("content".to_owned())))
+            }
+            _ => {
+                return Err(node.error(MetadataErrorKind::Invalid(
+                    "content is not an array".to_owned(),
+                )))
+            }
+        };
+        Ok(values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Node {
+                value,
+                pointer: format!("{}/{}", node.pointer, i),
+                step: self.step,
+            })
+            .collect())
+    }
+
+    /// Deserializes every entry of `content`, keeping the ones that are valid.
+    fn items<T: DeserializeOwned>(
+        &self,
+        errors: &mut Vec<MetadataError>,
+    ) -> Result<Vec<T>, MetadataError> {
+        Ok(self
+            .content()?
+            .iter()
+            .filter_map(|node| node.deserialize().map_err(|err| errors.push(err)).ok())
+            .collect())
+    }
+
+    /// The dependencies of a group, keeping ones with a compatibility range
+    /// that does not parse but reporting it.
+    fn dependencies(
+        &self,
+        errors: &mut Vec<MetadataError>,
+    ) -> Result<Vec<Dependency>, MetadataError> {
+        let mut dependencies = Vec::new();
+        for node in self.content()? {
+            let Some(dependency) = node
+                .deserialize::<Dependency>()
+                .map_err(|err| errors.push(err))
+                .ok()
+            else {
+                continue;
+            };
+            if let Some(Err(err)) = dependency
+                .compatibility_range
+                .as_deref()
+                .map(str::parse::<VersionRange>)
+            {
+                errors.push(MetadataError {
+                    pointer: format!("{}/compatibilityRange", node.pointer),
+                    step: self.step.to_owned(),
+                    kind: MetadataErrorKind::Invalid(format!(
+                        "{}, offering '{}' for every Spring Boot version",
+                        err, dependency.id
+                    )),
+                });
+            }
+            dependencies.push(dependency);
+        }
+        Ok(dependencies)
+    }
+
+    fn options<T: DeserializeOwned>(
+        &self,
+        errors: &mut Vec<MetadataError>,
+        is_default: impl Fn(&T) -> bool,
+    ) -> Result<Vec<T>, MetadataError> {
+        let items = self.items(errors)?;
+        let content = format!("{}/content", self.pointer);
+        if items.is_empty() {
+            return Err(MetadataError {
+                pointer: content,
+                step: self.step.to_owned(),
+                kind: MetadataErrorKind::NoOptions,
+            });
+        }
+        if !items.iter().any(is_default) {
+            errors.push(MetadataError {
+                pointer: content,
+                step: self.step.to_owned(),
+                kind: MetadataErrorKind::NoDefault,
+            });
+        }
+        Ok(items)
+    }
+
+    fn capability(
+        &self,
+        t: &str,
+        errors: &mut Vec<MetadataError>,
+    ) -> Result<Option<Capability>, MetadataError> {
+        let capability = match t {
+            "TEXT" => Capability::Text(self.deserialize()?),
+            "SINGLE_SELECT" => {
+                let Header {
+                    id,
+                    title,
+                    description,
+                } = self.deserialize()?;
+                Capability::SingleSelect(SingleSelectCapability {
+                    id,
+                    title,
+                    description,
+                    content: self.options(errors, |o: &SelectOption| o.default)?,
+                })
+            }
+            "ACTION" => {
+                let Header {
+                    id,
+                    title,
+                    description,
+                } = self.deserialize()?;
+                Capability::Action(ActionCapability {
+                    id,
+                    title,
+                    description,
+                    content: self.options(errors, |t: &ProjectType| t.default)?,
+                })
+            }
+            "HIERARCHICAL_MULTI_SELECT" => {
+                let Header {
+                    id,
+                    title,
+                    description,
+                } = self.deserialize()?;
+                let content = self
+                    .content()?
+                    .iter()
+                    .filter_map(|group| {
+                        let header: GroupHeader =
+                            group.deserialize().map_err(|err| errors.push(err)).ok()?;
+                        let content = group
+                            .dependencies(errors)
+                            .map_err(|err| errors.push(err))
+                            .ok()?;
+                        Some(DependencyGroup {
+                            name: header.name,
+                            content,
+                        })
+                    })
+                    .collect();
+                Capability::Dependencies(DependenciesCapability {
+                    id,
+                    title,
+                    description,
+                    content,
+                })
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(capability))
+    }
+}
+
+/// Escapes a key for use in a JSON pointer, see RFC 6901.
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    impl Capability {
+        fn id(&self) -> &str {
+            match self {
+                Capability::Text(c) => &c.id,
+                Capability::SingleSelect(c) => &c.id,
+                Capability::Action(c) => &c.id,
+                Capability::Dependencies(c) => &c.id,
+            }
+        }
+    }
+
+    fn dependencies(metadata: &InitializrMetadata) -> Vec<(&DependencyGroup, &Dependency)> {
+        metadata
+            .capabilities
+            .iter()
+            .filter_map(|c| match c {
+                Capability::Dependencies(c) => Some(c),
+                _ => None,
+            })
+            .flat_map(|c| c.content.iter())
+            .flat_map(|group| group.content.iter().map(move |dep| (group, dep)))
+            .collect()
+    }
+
+    #[test]
+    fn test_dependency_details() {
+        let json = json!({
+            "dependencies": {
+                "id": "dependencies",
+                "type": "HIERARCHICAL_MULTI_SELECT",
+                "title": "Project dependencies",
+                "content": [
+                    {
+                        "name": "Developer Tools",
+                        "content": [
+                            {
+                                "name": "Spring Modulith",
+                                "id": "modulith",
+                                "groupId": "org.springframework.modulith",
+                                "artifactId": "spring-modulith-starter-core",
+                                "scope": "compile",
+                                "description": "Support for building modular monolithic applications.",
+                                "compatibilityRange": "[3.3.0,3.5.0-M1)",
+                                "bom": "spring-modulith",
+                                "starter": true,
+                                "facets": ["web"],
+                                "links": [
+                                    {
+                                        "rel": "reference",
+                                        "href": "https://docs.spring.io/spring-boot/{bootVersion}/reference/web/servlet.html",
+                                        "templated": true
+                                    }
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            }
+        });
+
+        let (metadata, errors) = InitializrMetadata::from_json(json).unwrap();
+
+        assert!(errors.is_empty());
+        let deps = dependencies(&metadata);
+        assert_eq!(deps.len(), 1);
+        let (group, dep) = deps[0];
+        assert_eq!(group.name, "Developer Tools");
+        assert_eq!(
+            dep,
+            &Dependency {
+                id: "modulith".to_owned(),
+                name: "Spring Modulith".to_owned(),
+                description: Some(
+                    "Support for building modular monolithic applications.".to_owned()
+                ),
+                group_id: Some("org.springframework.modulith".to_owned()),
+                artifact_id: Some("spring-modulith-starter-core".to_owned()),
+                version: None,
+                scope: Some("compile".to_owned()),
+                starter: Some(true),
+                bom: Some("spring-modulith".to_owned()),
+                repository: None,
+                compatibility_range: Some("[3.3.0,3.5.0-M1)".to_owned()),
+                facets: vec!["web".to_owned()],
+                links: vec![Link {
+                    rel: "reference".to_owned(),
+                    href: "https://docs.spring.io/spring-boot/{bootVersion}/reference/web/servlet.html"
+                        .to_owned(),
+                    description: None,
+                    templated: true,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_configuration_and_tags() {
+        let json = json!({
+            "configuration": {
+                "env": {
+                    "fallbackApplicationName": "Application",
+                    "platform": {
+                        "compatibilityRange": "3.3.0"
+                    }
+                }
+            },
+            "types": {
+                "id": "type",
+                "type": "ACTION",
+                "title": "Type",
+                "content": [
+                    {
+                        "name": "Maven",
+                        "id": "maven-project",
+                        "description": "Generate a Maven based project archive.",
+                        "action": "/starter.zip",
+                        "tags": { "build": "maven", "format": "project" },
+                        "default": true
+                    }
+                ]
+            }
+        });
+
+        let (metadata, errors) = InitializrMetadata::from_json(json).unwrap();
+
+        assert!(errors.is_empty());
+        let env = metadata.configuration.clone().unwrap().env.unwrap();
+        assert_eq!(
+            env.fallback_application_name.as_deref(),
+            Some("Application")
+        );
+        assert_eq!(
+            env.platform.unwrap().compatibility_range.as_deref(),
+            Some("3.3.0")
+        );
+        let Some(Capability::Action(types)) =
+            metadata.capabilities.iter().find(|c| c.id() == "type")
+        else {
+            panic!("expected type action");
+        };
+        assert_eq!(types.title.as_deref(), Some("Type"));
+        assert_eq!(types.content[0].tags["build"], "maven");
+    }
+
+    #[test]
+    fn test_missing_action_reports_pointer() {
+        let json = json!({
+            "types": {
+                "id": "type",
+                "type": "ACTION",
+                "content": [
+                    {
+                        "name": "Maven",
+                        "id": "maven-project",
+                        "action": "/starter.zip",
+                        "default": true
+                    },
+                    {
+                        "name": "Maven POM",
+                        "id": "maven-build"
+                    }
+                ]
+            }
+        });
+
+        let (metadata, errors) = InitializrMetadata::from_json(json).unwrap();
+
+        assert_eq!(
+            errors,
+            vec![MetadataError {
+                pointer: "/types/content/1/action".to_owned(),
+                step: "type".to_owned(),
+                kind: MetadataErrorKind::MissingField("action".to_owned()),
+            }]
+        );
+        let Some(Capability::Action(types)) =
+            metadata.capabilities.iter().find(|c| c.id() == "type")
+        else {
+            panic!("expected type action");
+        };
+        assert_eq!(types.content.len(), 1);
+        assert_eq!(types.content[0].id, "maven-project");
+    }
+
+    #[test]
+    fn test_invalid_compatibility_range_is_reported() {
+        let json = json!({
+            "dependencies": {
+                "id": "dependencies",
+                "type": "HIERARCHICAL_MULTI_SELECT",
+                "content": [
+                    {
+                        "name": "Web",
+                        "content": [
+                            { "id": "web", "name": "Spring Web" },
+                            { "id": "legacy", "name": "Legacy", "compatibilityRange": "3.x" }
+                        ]
+                    }
+                ]
+            }
+        });
+
+        let (metadata, errors) = InitializrMetadata::from_json(json).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "step 'dependencies': invalid version '3.x', offering 'legacy' for every Spring Boot \
+             version at /dependencies/content/0/content/1/compatibilityRange"
+        );
+        let Some(Capability::Dependencies(dependencies)) = metadata.capabilities.first() else {
+            panic!("expected dependencies");
+        };
+        assert_eq!(
+            dependencies.content[0].content[1]
+                .compatibility_range
+                .as_deref(),
+            Some("3.x")
+        );
+    }
+
+    #[test]
+    fn test_lenient_keeps_usable_capabilities() {
+        let json = json!({
+            "languages": {
+                "id": "language",
+                "type": "SINGLE_SELECT",
+                "content": [
+                    { "id": "java", "name": "Java" },
+                    { "id": "kotlin", "name": "Kotlin" }
+                ]
+            },
+            "name": {
+                "type": "TEXT",
+                "content": "demo"
+            },
+            "packagings": {
+                "id": "packaging",
+                "type": "SINGLE_SELECT",
+                "content": []
+            },
+            "dependencies": {
+                "id": "dependencies",
+                "type": "HIERARCHICAL_MULTI_SELECT",
+                "content": [
+                    { "content": [{ "id": "web", "name": "Spring Web" }] },
+                    { "name": "SQL", "content": [{ "id": "jdbc" }, { "id": "h2", "name": "H2" }] }
+                ]
+            }
+        });
+
+        let (metadata, errors) = InitializrMetadata::from_json(json).unwrap();
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| (e.pointer.as_str(), e.kind.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/dependencies/content/0/name",
+                    MetadataErrorKind::MissingField("name".to_owned())
+                ),
+                (
+                    "/dependencies/content/1/content/0/name",
+                    MetadataErrorKind::MissingField("name".to_owned())
+                ),
+                ("/languages/content", MetadataErrorKind::NoDefault),
+                ("/name/id", MetadataErrorKind::MissingField("id".to_owned())),
+                ("/packagings/content", MetadataErrorKind::NoOptions),
+            ]
+        );
+        assert_eq!(
+            metadata
+                .capabilities
+                .iter()
+                .map(Capability::id)
+                .collect::<Vec<_>>(),
+            vec!["dependencies", "language"]
+        );
+        assert_eq!(
+            dependencies(&metadata)
+                .iter()
+                .map(|(_, dep)| dep.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["h2"]
+        );
+    }
+
+    #[test]
+    fn test_pointer_escaping() {
+        let json = json!({
+            "a/b": {
+                "id": "custom",
+                "type": "TEXT",
+                "content": 1
+            }
+        });
+
+        let (metadata, errors) = InitializrMetadata::from_json(json).unwrap();
+
+        assert!(metadata.capabilities.is_empty());
+        assert_eq!(errors[0].pointer, "/a~1b/content");
+        assert_eq!(
+            errors[0].to_string(),
+            "step 'custom': invalid type: integer `1`, expected a string at /a~1b/content"
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use anyhow::Result;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::Stylize,
+    terminal::{self, ClearType},
+};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use inquire::InquireError;
+use std::io::{self, Write};
+
+use crate::{
+    search,
+    steps::{Item, ItemKind},
+};
+
+const PAGE_SIZE: usize = 12;
+
+pub(crate) fn selected_summary(selected: &[String]) -> String {
+    if selected.is_empty() {
+        "nothing selected yet".to_owned()
+    } else {
+        format!("selected: {}", selected.join(", "))
+    }
+}
+
+fn item_label(item: &Item, with_group: bool) -> String {
+    match (&item.kind, with_group) {
+        (ItemKind::Dependency(group), true) => format!("{} ({}) [{}]", item.name, item.id, group),
+        _ => format!("{} ({})", item.name, item.id),
+    }
+}
+
+/// Replaces the selection of the shown items by the picked ones, keeping the
+/// order dependencies were first selected in.
+fn update_selection(selected: &mut Vec<String>, shown: &[&Item], picked: &[usize]) {
+    selected.retain(|id| {
+        shown
+            .iter()
+            .position(|item| &item.id == id)
+            .is_none_or(|index| picked.contains(&index))
+    });
+    for &index in picked {
+        if !selected.contains(&shown[index].id) {
+            selected.push(shown[index].id.clone());
+        }
+    }
+}
+
+enum Outcome {
+    Continue,
+    Submit,
+    Cancel,
+    Interrupt,
+}
+
+/// A multi-select over dependencies that shows the full description of the
+/// highlighted one, which inquire's fixed help message cannot.
+struct Picker<'a> {
+    items: &'a [&'a Item],
+    with_group: bool,
+    selected: &'a [String],
+    checked: Vec<bool>,
+    filter: String,
+    cursor: usize,
+}
+
+impl<'a> Picker<'a> {
+    fn new(items: &'a [&'a Item], with_group: bool, selected: &'a [String]) -> Self {
+        Self {
+            items,
+            with_group,
+            selected,
+            checked: items
+                .iter()
+                .map(|item| selected.contains(&item.id))
+                .collect(),
+            filter: String::new(),
+            cursor: 0,
+        }
+    }
+
+    /// The items matching the filter, best match first.
+    fn visible(&self) -> Vec<usize> {
+        if self.filter.is_empty() {
+            return (0..self.items.len()).collect();
+        }
+        let matcher = SkimMatcherV2::default();
+        let mut hits: Vec<(i64, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                search::score(&matcher, item, &self.filter).map(|score| (score, index))
+            })
+            .collect();
+        hits.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        hits.into_iter().map(|(_, index)| index).collect()
+    }
+
+    fn highlighted(&self) -> Option<&'a Item> {
+        self.visible()
+            .get(self.cursor)
+            .map(|&index| self.items[index])
+    }
+
+    fn picked(&self) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|&index| self.checked[index])
+            .collect()
+    }
+
+    /// The whole selection with the changes made in this picker.
+    fn selection(&self) -> Vec<String> {
+        let mut selection = self.selected.to_vec();
+        update_selection(&mut selection, self.items, &self.picked());
+        selection
+    }
+
+    fn handle(&mut self, key: KeyEvent) -> Outcome {
+        let visible = self.visible();
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => return Outcome::Submit,
+            KeyCode::Esc => return Outcome::Cancel,
+            KeyCode::Char('c') if ctrl => return Outcome::Interrupt,
+            KeyCode::Up if !visible.is_empty() => {
+                self.cursor = self.cursor.checked_sub(1).unwrap_or(visible.len() - 1);
+            }
+            KeyCode::Down if !visible.is_empty() => {
+                self.cursor = (self.cursor + 1) % visible.len();
+            }
+            KeyCode::PageUp => self.cursor = self.cursor.saturating_sub(PAGE_SIZE),
+            KeyCode::PageDown => {
+                self.cursor = (self.cursor + PAGE_SIZE).min(visible.len().saturating_sub(1));
+            }
+            KeyCode::Char(' ') => {
+                if let Some(&index) = visible.get(self.cursor) {
+                    self.checked[index] = !self.checked[index];
+                }
+            }
+            KeyCode::Right | KeyCode::Left => {
+                for &index in &visible {
+                    self.checked[index] = key.code == KeyCode::Right;
+                }
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.filter.push(c);
+                self.cursor = 0;
+            }
+            _ => {}
+        }
+        Outcome::Continue
+    }
+
+    /// The lines of the prompt, with the help under the options: the full
+    /// description of the highlighted item and the running selection.
+    fn lines(&self, message: &str, hidden: Option<&str>) -> Vec<String> {
+        let visible = self.visible();
+        let start = (self.cursor + 1).saturating_sub(PAGE_SIZE);
+        let mut lines = vec![format!("{} {} {}", "?".green(), message, self.filter)];
+        for (position, &index) in visible.iter().enumerate().skip(start).take(PAGE_SIZE) {
+            let mark = if self.checked[index] { "[x]" } else { "[ ]" };
+            let label = item_label(self.items[index], self.with_group);
+            lines.push(if position == self.cursor {
+                format!("{} {} {}", ">".cyan(), mark.cyan(), label.cyan())
+            } else {
+                format!("  {} {}", mark, label)
+            });
+        }
+        if visible.is_empty() {
+            lines.push("  no dependency matches the filter".to_owned());
+        }
+        if let Some(description) = self
+            .highlighted()
+            .and_then(|item| item.description.as_ref())
+        {
+            lines.push(description.clone().cyan().to_string());
+        }
+        let mut help = format!(
+            "{}, space to select, type to filter, esc to go back",
+            selected_summary(&self.selection())
+        );
+        if let Some(hidden) = hidden {
+            help.push_str(", ");
+            help.push_str(hidden);
+        }
+        lines.push(format!("[{}]", help).cyan().to_string());
+        lines
+    }
+}
+
+/// The terminal rows taken by the lines once wrapped at `columns`.
+fn rows(lines: &[String], columns: u16) -> u16 {
+    let columns = usize::from(columns.max(1));
+    lines
+        .iter()
+        .map(|line| {
+            let width = console_width(line);
+            width.div_ceil(columns).max(1) as u16
+        })
+        .sum()
+}
+
+/// The printed width of a line, without its color escape sequences.
+fn console_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut escape = false;
+    for c in line.chars() {
+        match c {
+            '\x1b' => escape = true,
+            'm' if escape => escape = false,
+            _ if escape => {}
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+/// Restores the terminal however the picker ends.
+struct RawMode;
+
+impl RawMode {
+    fn enable(out: &mut impl Write) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        queue!(out, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let mut err = io::stderr();
+        let _ = queue!(err, cursor::Show);
+        let _ = err.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Lets the user check items, updating `selected` when they confirm with
+/// enter. Esc keeps the selection as it was.
+pub(crate) fn pick(
+    message: &str,
+    items: &[&Item],
+    with_group: bool,
+    hidden: Option<&str>,
+    selected: &mut Vec<String>,
+) -> Result<()> {
+    let mut picker = Picker::new(items, with_group, selected);
+    let mut out = io::stderr();
+    let raw = RawMode::enable(&mut out)?;
+    let mut drawn = 0;
+    let outcome = loop {
+        if drawn > 0 {
+            queue!(out, cursor::MoveUp(drawn), cursor::MoveToColumn(0))?;
+        }
+        queue!(out, terminal::Clear(ClearType::FromCursorDown))?;
+        let lines = picker.lines(message, hidden);
+        for line in &lines {
+            write!(out, "{}\r\n", line)?;
+        }
+        out.flush()?;
+        drawn = rows(&lines, terminal::size()?.0);
+
+        match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => match picker.handle(key) {
+                Outcome::Continue => {}
+                outcome => break outcome,
+            },
+            _ => {}
+        }
+    };
+
+    queue!(
+        out,
+        cursor::MoveUp(drawn),
+        cursor::MoveToColumn(0),
+        terminal::Clear(ClearType::FromCursorDown)
+    )?;
+    let answer = match outcome {
+        Outcome::Submit => picker.selection().join(", ").cyan().to_string(),
+        _ => "<canceled>".dark_grey().to_string(),
+    };
+    write!(out, "{} {} {}\r\n", "?".green(), message, answer)?;
+    drop(raw);
+
+    match outcome {
+        Outcome::Submit => *selected = picker.selection(),
+        Outcome::Interrupt => return Err(InquireError::OperationInterrupted.into()),
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dependencies() -> Vec<Item> {
+        [
+            ("postgresql", "PostgreSQL Driver", "SQL"),
+            ("data-jpa", "Spring Data JPA", "SQL"),
+        ]
+        .iter()
+        .map(|(id, name, group)| {
+            Item::new_dependency(id.to_string(), name.to_string(), group.to_string())
+                .with_description(Some(format!("The {} description.", name)))
+        })
+        .collect()
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_item_label() {
+        let values = dependencies();
+
+        assert_eq!(
+            item_label(&values[0], false),
+            "PostgreSQL Driver (postgresql)"
+        );
+        assert_eq!(
+            item_label(&values[1], true),
+            "Spring Data JPA (data-jpa) [SQL]"
+        );
+    }
+
+    #[test]
+    fn test_update_selection_keeps_other_groups() {
+        let values = dependencies();
+        let sql: Vec<&Item> = values.iter().collect();
+        let mut selected = vec!["postgresql".to_owned(), "web".to_owned()];
+
+        update_selection(&mut selected, &sql, &[1]);
+
+        assert_eq!(selected, vec!["web", "data-jpa"]);
+        assert_eq!(selected_summary(&selected), "selected: web, data-jpa");
+        assert_eq!(selected_summary(&[]), "nothing selected yet");
+    }
+
+    #[test]
+    fn test_help_follows_the_highlighted_item() {
+        let values = dependencies();
+        let items: Vec<&Item> = values.iter().collect();
+        let selected = vec!["web".to_owned()];
+        let mut picker = Picker::new(&items, false, &selected);
+
+        let help = |picker: &Picker| picker.lines("Select:", None).join("\n");
+        assert!(help(&picker).contains("The PostgreSQL Driver description."));
+
+        picker.handle(key(KeyCode::Down));
+        picker.handle(key(KeyCode::Char(' ')));
+        assert!(help(&picker).contains("The Spring Data JPA description."));
+        assert!(help(&picker).contains("selected: web, data-jpa,"));
+    }
+
+    #[test]
+    fn test_filter_keeps_checked_items() {
+        let values = dependencies();
+        let items: Vec<&Item> = values.iter().collect();
+        let mut picker = Picker::new(&items, false, &[]);
+
+        for c in "jpa".chars() {
+            picker.handle(key(KeyCode::Char(c)));
+        }
+        assert_eq!(picker.visible(), vec![1]);
+        picker.handle(key(KeyCode::Char(' ')));
+        picker.handle(key(KeyCode::Backspace));
+        picker.handle(key(KeyCode::Backspace));
+        picker.handle(key(KeyCode::Backspace));
+
+        assert_eq!(picker.visible(), vec![0, 1]);
+        assert_eq!(picker.selection(), vec!["data-jpa"]);
+    }
+
+    #[test]
+    fn test_rows() {
+        let lines = vec![
+            "a".repeat(10),
+            String::new(),
+            "b".repeat(25).cyan().to_string(),
+        ];
+
+        assert_eq!(rows(&lines, 10), 1 + 1 + 3);
+    }
+}
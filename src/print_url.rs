@@ -0,0 +1,113 @@
+use anyhow::Result;
+use std::io::Write;
+
+use crate::{
+    args::DEFAULT_URL,
+    request::{build_url, ResponseStep},
+};
+
+/// Quotes a value for POSIX shells when it contains anything but safe characters.
+fn shell_quote(value: &str) -> String {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.,/:=@%+".contains(c));
+    if safe {
+        value.to_owned()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// The `spring-boot-cli` invocation generating the same project without prompting.
+fn cli_command(url: &str, responses: &[ResponseStep]) -> String {
+    let mut args = vec![env!("CARGO_PKG_NAME").to_owned()];
+    if url != DEFAULT_URL {
+        args.push(format!("--url {}", shell_quote(url)));
+    }
+    args.push("--non-interactive".to_owned());
+    args.extend(
+        responses
+            .iter()
+            .filter(|r| !r.response.is_empty())
+            .map(|r| {
+                format!(
+                    "--set {}",
+                    shell_quote(&format!("{}={}", r.step.name, r.response))
+                )
+            }),
+    );
+    args.join(" ")
+}
+
+/// Prints the generation url and equivalent `curl` and CLI commands instead of
+/// downloading the project.
+pub(crate) fn print_url(url: &str, responses: &[ResponseStep], out: &mut impl Write) -> Result<()> {
+    let generate_url = build_url(url, responses)?;
+    writeln!(out, "{}", generate_url)?;
+    writeln!(out)?;
+    writeln!(out, "curl -fOJ {}", shell_quote(generate_url.as_str()))?;
+    writeln!(out, "{}", cli_command(url, responses))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::steps::{Item, Step, StepKind};
+
+    fn responses() -> Vec<ResponseStep> {
+        let text = |name: &str, response: &str| ResponseStep {
+            step: Step {
+                name: name.to_owned(),
+                kind: StepKind::Text {
+                    default: String::new(),
+                },
+            },
+            response: response.to_owned(),
+        };
+        vec![
+            ResponseStep {
+                step: Step {
+                    name: "type".to_owned(),
+                    kind: StepKind::Action {
+                        default: "maven-project".to_owned(),
+                        values: vec![Item::new_action(
+                            "maven-project".to_owned(),
+                            "Maven".to_owned(),
+                            "/starter.zip".to_owned(),
+                        )],
+                    },
+                },
+                response: "maven-project".to_owned(),
+            },
+            text("groupId", "org.acme"),
+            text("description", "Billing service's API"),
+            text("dependencies", "web,data-jpa"),
+            text("version", ""),
+        ]
+    }
+
+    #[test]
+    fn test_print_url() {
+        let mut out = Vec::new();
+        print_url(DEFAULT_URL, &responses(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "https://start.spring.io/starter.zip?type=maven-project&groupId=org.acme&description=Billing+service%27s+API&dependencies=web%2Cdata-jpa\n\
+             \n\
+             curl -fOJ 'https://start.spring.io/starter.zip?type=maven-project&groupId=org.acme&description=Billing+service%27s+API&dependencies=web%2Cdata-jpa'\n\
+             spring-boot-cli --non-interactive --set type=maven-project --set groupId=org.acme \
+             --set 'description=Billing service'\\''s API' --set dependencies=web,data-jpa\n"
+        );
+    }
+
+    #[test]
+    fn test_cli_command_keeps_custom_url() {
+        assert_eq!(
+            cli_command("https://start.acme.org", &responses()[..1]),
+            "spring-boot-cli --url https://start.acme.org --non-interactive --set type=maven-project"
+        );
+    }
+}
@@ -1,6 +1,11 @@
-use crate::steps::{ItemKind, Step, StepKind};
+use crate::{
+    cache::{CacheMode, CachedMetadata, MetadataCache},
+    error::CliError,
+    steps::{ItemKind, Step, StepKind},
+};
 use anyhow::{Context, Result};
 use std::str::FromStr;
+use ureq::{http::Response, typestate::WithoutBody, Body, RequestBuilder};
 use url::Url;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -9,30 +14,119 @@ pub struct ResponseStep {
     pub response: String,
 }
 
-pub fn get_options(url: &str) -> Result<serde_json::Value> {
+/// Sends the request, turning error statuses into [`CliError::Server`] with
+/// the message from the Initializr error body.
+fn call(request: RequestBuilder<WithoutBody>) -> Result<Response<Body>, CliError> {
+    let mut response = request
+        .config()
+        .http_status_as_error(false)
+        .build()
+        .call()
+        .map_err(CliError::Network)?;
+
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        let body = response.body_mut().read_to_string().unwrap_or_default();
+        return Err(CliError::from_response(status.as_u16(), &body));
+    }
+    Ok(response)
+}
+
+pub fn get_options(
+    url: &str,
+    cache: Option<&MetadataCache>,
+    mode: CacheMode,
+) -> Result<serde_json::Value> {
     let url = Url::from_str(url)?.join("/metadata/config")?;
+    let cached = cache.and_then(|c| c.load(url.as_str()));
+
+    if mode == CacheMode::Offline {
+        return cached
+            .map(|c| c.body)
+            .with_context(|| format!("no cached metadata for {}, run without --offline", &url));
+    }
+    let cached = cached.filter(|_| mode == CacheMode::Revalidate);
+
+    eprintln!("getting parameter from {}", &url);
+    let mut request = ureq::get(url.as_str());
+    if let Some(c) = &cached {
+        if let Some(etag) = &c.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &c.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+    }
+
+    // Only an unreachable server falls back to the cache, error statuses are
+    // reported as they are
+    let mut response = match call(request) {
+        Ok(response) => response,
+        Err(CliError::Network(err)) => match cached {
+            Some(cached) => {
+                eprintln!("could not reach {} ({}), using cached metadata", &url, err);
+                return Ok(cached.body);
+            }
+            None => return Err(CliError::Network(err).into()),
+        },
+        Err(err) => return Err(err.into()),
+    };
+
+    if response.status() == 304 {
+        return cached
+            .map(|c| c.body)
+            .context("server answered 304 Not Modified without cached metadata");
+    }
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned)
+    };
+    let etag = header("etag");
+    let last_modified = header("last-modified");
 
-    println!("getting parameter from {}", &url);
-    ureq::get(url.as_str())
-        .call()?
+    let body: serde_json::Value = response
         .body_mut()
         .read_json()
-        .context("expect json back")
+        .context("expect json back")?;
+
+    if let Some(cache) = cache {
+        let cached = CachedMetadata {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            body,
+        };
+        if let Err(err) = cache.store(&cached) {
+            eprintln!("could not cache metadata: {:#}", err);
+        }
+        return Ok(cached.body);
+    }
+    Ok(body)
 }
 
-pub fn get_zip(url: &str, responses: &[ResponseStep]) -> Result<(Option<String>, Vec<u8>)> {
+/// The url of the selected action with every non-empty answer as query parameter.
+pub fn build_url(url: &str, responses: &[ResponseStep]) -> Result<Url> {
     let url = Url::parse(url)?;
 
     let mut url = url.join(get_url_path(responses).context("action need to be set")?)?;
     let mut querys = url.query_pairs_mut();
     responses.iter().for_each(|q| {
         if !q.response.is_empty() {
-            querys.append_pair(&q.step.name, &q.response.replace(" ", "%20"));
+            querys.append_pair(&q.step.name, &q.response);
         }
     });
     drop(querys);
+    Ok(url)
+}
+
+pub fn get_zip(url: &str, responses: &[ResponseStep]) -> Result<(Option<String>, Vec<u8>)> {
+    let url = build_url(url, responses)?;
 
-    let mut response = ureq::get(url.as_str()).call()?;
+    let mut response = call(ureq::get(url.as_str()))?;
 
     let content_file = &response
         .headers()
@@ -51,7 +145,7 @@ pub fn get_zip(url: &str, responses: &[ResponseStep]) -> Result<(Option<String>,
 
     let body = response.body_mut();
 
-    let buf: Vec<u8> = body.read_to_vec()?;
+    let buf: Vec<u8> = body.read_to_vec().map_err(CliError::Network)?;
 
     Ok((content_file.to_owned().map(|x| format!("./{}", x)), buf))
 }
@@ -77,6 +171,7 @@ mod test {
 
     use super::*;
     use httpmock::prelude::*;
+    use serde_json::json;
 
     #[test]
     fn test_get_zip() {
@@ -89,7 +184,7 @@ mod test {
 
         let res = get_zip(
             &server.url("/"),
-            &vec![ResponseStep {
+            &[ResponseStep {
                 step: Step {
                     name: "type".to_owned(),
                     kind: StepKind::Action {
@@ -110,4 +205,150 @@ mod test {
         let res = res.expect("is ok");
         assert_eq!(res.1, buf);
     }
+
+    #[test]
+    fn test_get_zip_surfaces_server_message() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/starter.zip");
+            then.status(400).json_body(json!({
+                "status": 400,
+                "error": "Bad Request",
+                "message": "Dependency 'modulith' is not compatible with Spring Boot 3.5.0",
+                "path": "/starter.zip"
+            }));
+        });
+
+        let err = get_zip(
+            &server.url("/"),
+            &[ResponseStep {
+                step: Step {
+                    name: "type".to_owned(),
+                    kind: StepKind::Action {
+                        default: "".to_string(),
+                        values: vec![Item::new_action(
+                            "maven-project".to_string(),
+                            "Maven".to_owned(),
+                            "/starter.zip".to_owned(),
+                        )],
+                    },
+                },
+                response: "maven-project".to_owned(),
+            }],
+        )
+        .unwrap_err();
+
+        assert_eq!(CliError::exit_code_of(&err), 4);
+        assert_eq!(
+            err.to_string(),
+            "server rejected the request (400): Dependency 'modulith' is not compatible with Spring Boot 3.5.0"
+        );
+    }
+
+    #[test]
+    fn test_get_options_revalidates_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::new(dir.path());
+        let server = MockServer::start();
+        let body = json!({"name": {"id": "name", "type": "TEXT", "content": "demo"}});
+
+        let mut fresh = server.mock(|when, then| {
+            when.method(GET).path("/metadata/config");
+            then.status(200)
+                .header("etag", "\"v1\"")
+                .json_body(body.clone());
+        });
+        let res = get_options(&server.url("/"), Some(&cache), CacheMode::Revalidate);
+        fresh.assert();
+        assert_eq!(res.unwrap(), body);
+        fresh.delete();
+
+        let not_modified = server.mock(|when, then| {
+            when.method(GET)
+                .path("/metadata/config")
+                .header("If-None-Match", "\"v1\"");
+            then.status(304);
+        });
+        let res = get_options(&server.url("/"), Some(&cache), CacheMode::Revalidate);
+        not_modified.assert();
+        assert_eq!(res.unwrap(), body);
+    }
+
+    #[test]
+    fn test_get_options_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::new(dir.path());
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/metadata/config");
+            then.status(200).json_body(json!({}));
+        });
+
+        let res = get_options(&server.url("/"), Some(&cache), CacheMode::Offline);
+        assert!(res.is_err());
+
+        let body = json!({"name": {"id": "name", "type": "TEXT", "content": "demo"}});
+        cache
+            .store(&CachedMetadata {
+                url: server.url("/metadata/config"),
+                etag: None,
+                last_modified: None,
+                body: body.clone(),
+            })
+            .unwrap();
+
+        let res = get_options(&server.url("/"), Some(&cache), CacheMode::Offline);
+        assert_eq!(res.unwrap(), body);
+        mock.assert_calls(0);
+    }
+
+    #[test]
+    fn test_get_options_serves_stale_when_unreachable() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::new(dir.path());
+        // nothing listens on port 1, so the connection is refused
+        let url = "http://127.0.0.1:1/".to_owned();
+        let body = json!({"name": {"id": "name", "type": "TEXT", "content": "demo"}});
+        cache
+            .store(&CachedMetadata {
+                url: format!("{}metadata/config", url),
+                etag: Some("\"v1\"".to_owned()),
+                last_modified: None,
+                body: body.clone(),
+            })
+            .unwrap();
+
+        let res = get_options(&url, Some(&cache), CacheMode::Revalidate);
+        assert_eq!(res.unwrap(), body);
+
+        let err = get_options(&url, Some(&cache), CacheMode::Refresh).unwrap_err();
+        assert_eq!(CliError::exit_code_of(&err), 3);
+    }
+
+    #[test]
+    fn test_get_options_reports_server_errors_despite_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::new(dir.path());
+        let server = MockServer::start();
+        cache
+            .store(&CachedMetadata {
+                url: server.url("/metadata/config"),
+                etag: Some("\"v1\"".to_owned()),
+                last_modified: None,
+                body: json!({}),
+            })
+            .unwrap();
+        server.mock(|when, then| {
+            when.method(GET).path("/metadata/config");
+            then.status(503).body("Service Unavailable");
+        });
+
+        let err = get_options(&server.url("/"), Some(&cache), CacheMode::Revalidate).unwrap_err();
+
+        assert_eq!(CliError::exit_code_of(&err), 4);
+        assert_eq!(
+            err.to_string(),
+            "server rejected the request (503): Service Unavailable"
+        );
+    }
 }
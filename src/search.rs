@@ -0,0 +1,185 @@
+use anyhow::{bail, Result};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use serde_json::{json, Value};
+use std::io::Write;
+
+use crate::{
+    args::OutputFormat,
+    list::Table,
+    steps::{Item, ItemKind, Step, StepKind},
+};
+
+/// Matches on the id or name count more than ones on the group or description.
+const PRIMARY_WEIGHT: i64 = 2;
+
+pub(crate) fn score(matcher: &SkimMatcherV2, item: &Item, term: &str) -> Option<i64> {
+    let group = match &item.kind {
+        ItemKind::Dependency(group) => Some(group.as_str()),
+        _ => None,
+    };
+    [
+        matcher
+            .fuzzy_match(&item.id, term)
+            .map(|s| s * PRIMARY_WEIGHT),
+        matcher
+            .fuzzy_match(&item.name, term)
+            .map(|s| s * PRIMARY_WEIGHT),
+        group.and_then(|group| matcher.fuzzy_match(group, term)),
+        item.description
+            .as_deref()
+            .and_then(|description| matcher.fuzzy_match(description, term)),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+}
+
+/// Dependencies matching every whitespace separated term of the query, best match first.
+pub(crate) fn search<'a>(steps: &'a [Step], query: &str) -> Vec<&'a Item> {
+    let matcher = SkimMatcherV2::default();
+    let terms: Vec<&str> = query.split_whitespace().collect();
+
+    let mut hits: Vec<(i64, &Item)> = steps
+        .iter()
+        .filter_map(|step| match &step.kind {
+            StepKind::MultiSelect { values } => Some(values),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| {
+            terms
+                .iter()
+                .map(|term| score(&matcher, item, term))
+                .sum::<Option<i64>>()
+                .map(|score| (score, item))
+        })
+        .collect();
+    hits.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    hits.into_iter().map(|(_, item)| item).collect()
+}
+
+pub(crate) fn print_search(
+    steps: &[Step],
+    query: &str,
+    limit: usize,
+    format: OutputFormat,
+    out: &mut impl Write,
+) -> Result<()> {
+    let hits: Vec<&Item> = search(steps, query).into_iter().take(limit).collect();
+    if hits.is_empty() && format != OutputFormat::Json {
+        bail!(
+            "no dependency matches '{}', try a shorter query or `list deps`",
+            query
+        );
+    }
+
+    Table {
+        columns: vec![
+            ("id", "ID"),
+            ("name", "NAME"),
+            ("group", "GROUP"),
+            ("description", "DESCRIPTION"),
+        ],
+        rows: hits
+            .iter()
+            .map(|item| {
+                let group = match &item.kind {
+                    ItemKind::Dependency(group) => json!(group),
+                    _ => Value::Null,
+                };
+                vec![
+                    json!(item.id),
+                    json!(item.name),
+                    group,
+                    json!(item.description),
+                ]
+            })
+            .collect(),
+    }
+    .print(format, out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn steps() -> Vec<Step> {
+        let dep = |id: &str, name: &str, group: &str, description: &str| {
+            Item::new_dependency(id.to_owned(), name.to_owned(), group.to_owned())
+                .with_description(Some(description.to_owned()))
+        };
+        vec![Step {
+            name: "dependencies".to_owned(),
+            kind: StepKind::MultiSelect {
+                values: vec![
+                    dep(
+                        "web",
+                        "Spring Web",
+                        "Web",
+                        "Build web, including RESTful, applications using Spring MVC.",
+                    ),
+                    dep(
+                        "data-jpa",
+                        "Spring Data JPA",
+                        "SQL",
+                        "Persist data in SQL stores with Java Persistence API.",
+                    ),
+                    dep(
+                        "postgresql",
+                        "PostgreSQL Driver",
+                        "SQL",
+                        "A JDBC and R2DBC driver that allows Java programs to connect to a PostgreSQL database.",
+                    ),
+                    dep(
+                        "security",
+                        "Spring Security",
+                        "Security",
+                        "Highly customizable authentication and access-control framework.",
+                    ),
+                ],
+            },
+        }]
+    }
+
+    fn ids(steps: &[Step], query: &str) -> Vec<String> {
+        search(steps, query)
+            .iter()
+            .map(|item| item.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_search_ranks_names_first() {
+        let steps = steps();
+
+        assert_eq!(ids(&steps, "jpa")[0], "data-jpa");
+        assert_eq!(ids(&steps, "postgres")[0], "postgresql");
+        assert_eq!(ids(&steps, "auth"), vec!["security"]);
+    }
+
+    #[test]
+    fn test_search_matches_group_and_every_term() {
+        let steps = steps();
+
+        let sql = ids(&steps, "sql");
+        assert!(sql.contains(&"data-jpa".to_owned()));
+        assert!(sql.contains(&"postgresql".to_owned()));
+        assert_eq!(ids(&steps, "sql driver")[0], "postgresql");
+        assert!(!ids(&steps, "sql driver").contains(&"web".to_owned()));
+        assert!(ids(&steps, "zzz").is_empty());
+    }
+
+    #[test]
+    fn test_print_search() {
+        let mut out = Vec::new();
+        print_search(&steps(), "secu", 1, OutputFormat::Plain, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "security\n");
+
+        let mut out = Vec::new();
+        let err = print_search(&steps(), "zzz", 10, OutputFormat::Table, &mut out).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no dependency matches 'zzz', try a shorter query or `list deps`"
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use anyhow::{Context, Result};
+use crate::{
+    metadata::{Capability, InitializrMetadata},
+    version::{Version, VersionRange},
+};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -6,6 +9,9 @@ pub struct Item {
     pub id: String,
     pub name: String,
     pub kind: ItemKind,
+    pub description: Option<String>,
+    /// The Spring Boot versions the item works with, as sent by the server.
+    pub compatibility_range: Option<String>,
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ItemKind {
@@ -20,6 +26,8 @@ impl Item {
             id,
             name,
             kind: ItemKind::Default,
+            description: None,
+            compatibility_range: None,
         }
     }
     pub fn new_action(id: String, name: String, action: String) -> Self {
@@ -27,6 +35,8 @@ impl Item {
             id,
             name,
             kind: ItemKind::Action(action),
+            description: None,
+            compatibility_range: None,
         }
     }
     pub fn new_dependency(id: String, name: String, group: String) -> Self {
@@ -34,8 +44,26 @@ impl Item {
             id,
             name,
             kind: ItemKind::Dependency(group),
+            description: None,
+            compatibility_range: None,
         }
     }
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+    pub fn with_compatibility_range(mut self, range: Option<String>) -> Self {
+        self.compatibility_range = range;
+        self
+    }
+    /// Whether the item can be used with the given Spring Boot version, items
+    /// without a valid range being compatible with every version.
+    pub fn is_compatible_with(&self, boot_version: &Version) -> bool {
+        self.compatibility_range
+            .as_deref()
+            .and_then(|range| range.parse::<VersionRange>().ok())
+            .is_none_or(|range| range.contains(boot_version))
+    }
 }
 
 impl fmt::Display for Item {
@@ -48,7 +76,6 @@ impl fmt::Display for Item {
     }
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
-
 pub struct Step {
     pub name: String,
     pub kind: StepKind,
@@ -62,119 +89,124 @@ pub enum StepKind {
     MultiSelect { values: Vec<Item> },
 }
 
-impl Step {
-    pub fn from_json(json: serde_json::Value) -> Result<Vec<Step>> {
-        let json = json.as_object().context("json")?;
-
-        let mut list = Vec::with_capacity(json.len() - 1);
-
-        for (key, body) in json {
-            if key == "configuration" {
-                continue;
-            }
+/// The prompt order of the Initializr web UI, steps it does not mention going last.
+pub const DEFAULT_STEP_ORDER: &[&str] = &[
+    "type",
+    "language",
+    "bootVersion",
+    "groupId",
+    "artifactId",
+    "name",
+    "description",
+    "packageName",
+    "packaging",
+    "javaVersion",
+    "dependencies",
+];
 
-            let Some(t) = body["type"].as_str() else {
-                continue;
-            };
+impl Step {
+    /// Sorts steps by their position in `order`. Steps missing from it go last
+    /// in their current order, which is the order of their keys in the
+    /// metadata JSON when coming from `from_metadata` (`types` for the `type`
+    /// step), so the result is the same on every run.
+    pub fn sort_by_order<S: AsRef<str>>(steps: &mut [Step], order: &[S]) {
+        steps.sort_by_key(|step| {
+            order
+                .iter()
+                .position(|id| id.as_ref() == step.name)
+                .unwrap_or(order.len())
+        });
+    }
 
-            let kind: StepKind = match t {
-                "TEXT" => StepKind::Text {
-                    default: body["content"]
-                        .as_str()
-                        .context("get default from TextStep")?
-                        .to_string(),
+    pub fn from_metadata(metadata: &InitializrMetadata) -> Vec<Step> {
+        metadata
+            .capabilities
+            .iter()
+            .map(|capability| match capability {
+                Capability::Text(c) => Step {
+                    name: c.id.clone(),
+                    kind: StepKind::Text {
+                        default: c.content.clone(),
+                    },
                 },
-                "SINGLE_SELECT" => {
-                    let content = body["content"]
-                        .as_array()
-                        .context("get content single select")?;
-
-                    let default = content
-                        .iter()
-                        .find(|v| v["default"].as_bool().expect("to be a boolean"))
-                        .expect("to have a default value")["id"]
-                        .as_str()
-                        .expect("to have id")
-                        .to_owned();
-
-                    let values = content
-                        .iter()
-                        .map(|v| {
-                            Item::new_default(
-                                v["id"].as_str().expect("to contain id").to_string(),
-                                v["name"].as_str().expect("to contain name").to_string(),
-                            )
-                        })
-                        .collect();
-
-                    StepKind::SingleSelect { default, values }
-                }
-                "ACTION" => {
-                    let content = body["content"].as_array().context("get content action")?;
-
-                    let default = content
-                        .iter()
-                        .find(|v| v["default"].as_bool().expect("to be a boolean"))
-                        .expect("to have a default value")["id"]
-                        .as_str()
-                        .expect("to have id")
-                        .to_owned();
-
-                    let values = content
-                        .iter()
-                        .map(|v| {
-                            Item::new_action(
-                                v["id"].as_str().expect("to contain id").to_string(),
-                                v["name"].as_str().expect("to contain name").to_string(),
-                                v["action"].as_str().expect("to contain action").to_string(),
-                            )
-                        })
-                        .collect();
-
-                    StepKind::Action { default, values }
-                }
-                "HIERARCHICAL_MULTI_SELECT" => StepKind::MultiSelect {
-                    values: body["content"]
-                        .as_array()
-                        .context("expectet content")?
-                        .iter()
-                        .flat_map(|v| {
-                            let group = v["name"]
-                                .as_str()
-                                .expect("group to contain name")
-                                .to_string();
-                            v["content"]
-                                .as_array()
-                                .expect("to contain content")
-                                .iter()
-                                .map(move |v| {
+                Capability::SingleSelect(c) => Step {
+                    name: c.id.clone(),
+                    kind: StepKind::SingleSelect {
+                        default: default_id(&c.content, |o| (&o.id, o.default)),
+                        values: c
+                            .content
+                            .iter()
+                            .map(|o| Item::new_default(o.id.clone(), o.name.clone()))
+                            .collect(),
+                    },
+                },
+                Capability::Action(c) => Step {
+                    name: c.id.clone(),
+                    kind: StepKind::Action {
+                        default: default_id(&c.content, |t| (&t.id, t.default)),
+                        values: c
+                            .content
+                            .iter()
+                            .map(|t| {
+                                Item::new_action(t.id.clone(), t.name.clone(), t.action.clone())
+                                    .with_description(t.description.clone())
+                            })
+                            .collect(),
+                    },
+                },
+                Capability::Dependencies(c) => Step {
+                    name: c.id.clone(),
+                    kind: StepKind::MultiSelect {
+                        values: c
+                            .content
+                            .iter()
+                            .flat_map(|group| {
+                                group.content.iter().map(|dep| {
                                     Item::new_dependency(
-                                        v["id"].as_str().expect("to contain id").to_string(),
-                                        v["name"].as_str().expect("to contain name").to_string(),
-                                        group.clone(),
+                                        dep.id.clone(),
+                                        dep.name.clone(),
+                                        group.name.clone(),
                                     )
+                                    .with_description(dep.description.clone())
+                                    .with_compatibility_range(dep.compatibility_range.clone())
                                 })
-                        })
-                        .collect(),
+                            })
+                            .collect(),
+                    },
                 },
-                _ => continue,
-            };
-            list.push(Step {
-                name: body["id"].as_str().context("expect to have id")?.to_owned(),
-                kind,
-            });
-        }
-        Ok(list)
+            })
+            .collect()
     }
 }
 
+/// The id of the option marked as default, falling back to the first option.
+fn default_id<T>(options: &[T], id_and_default: impl Fn(&T) -> (&String, bool)) -> String {
+    options
+        .iter()
+        .map(&id_and_default)
+        .find(|(_, default)| *default)
+        .or_else(|| options.first().map(&id_and_default))
+        .map(|(id, _)| id.clone())
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod test {
     use crate::steps;
 
     use super::*;
+    use anyhow::Result;
     use serde_json::json;
 
+    /// Parses the Initializr metadata, failing on the first malformed node.
+    fn from_json(json: serde_json::Value) -> Result<Vec<Step>> {
+        let (metadata, errors) = InitializrMetadata::from_json(json)?;
+        match errors.into_iter().next() {
+            Some(err) => Err(err.into()),
+            None => Ok(Step::from_metadata(&metadata)),
+        }
+    }
+
     #[test]
     fn test_text() {
         let json = json!({
@@ -184,7 +216,7 @@ mod test {
                 "content":"test"
             },
         });
-        let steps = Step::from_json(json);
+        let steps = from_json(json);
         assert!(steps.is_ok());
         let steps = steps.unwrap();
 
@@ -226,7 +258,7 @@ mod test {
                 }
             ]
         }});
-        let steps = Step::from_json(json);
+        let steps = from_json(json);
         assert!(steps.is_ok());
         let steps = steps.unwrap();
 
@@ -265,7 +297,7 @@ mod test {
                 }
             ]
         }});
-        let steps = Step::from_json(json);
+        let steps = from_json(json);
         assert!(steps.is_ok());
         let steps = steps.unwrap();
 
@@ -285,6 +317,41 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_dependency_compatibility_range() {
+        let json = json!({
+        "dependencies": {
+            "id": "dependencies",
+            "type": "HIERARCHICAL_MULTI_SELECT",
+            "content": [
+                {
+                    "name": "Developer Tools",
+                    "content": [
+                        {
+                            "name": "Spring Modulith",
+                            "id": "modulith",
+                            "compatibilityRange": "[3.3.0,3.5.0-M1)"
+                        },
+                        {
+                            "name": "Lombok",
+                            "id": "lombok"
+                        }
+                    ]
+                }
+            ]
+        }});
+        let steps = from_json(json).unwrap();
+
+        let StepKind::MultiSelect { values } = &steps[0].kind else {
+            panic!("expected multi select");
+        };
+        let old: Version = "3.4.2".parse().unwrap();
+        let new: Version = "3.5.0-M1".parse().unwrap();
+        assert!(values[0].is_compatible_with(&old));
+        assert!(!values[0].is_compatible_with(&new));
+        assert!(values[1].is_compatible_with(&new));
+    }
+
     #[test]
     fn test_action_parse() {
         let json: serde_json::Value = json!({
@@ -299,7 +366,7 @@ mod test {
                    "default": true
                }]
             }});
-        let steps = Step::from_json(json);
+        let steps = from_json(json);
         assert!(steps.is_ok());
         let steps = steps.unwrap();
 
@@ -350,39 +417,36 @@ mod test {
                 "content":"test"
             },
         });
-        let steps = Step::from_json(json);
+        let steps = from_json(json);
         assert!(steps.is_ok());
         let steps = steps.unwrap();
 
         assert_eq!(steps.len(), 2);
 
-        let _ = steps
-            .iter()
-            .map(|s| match &s.kind {
-                StepKind::Text { default } => {
-                    assert_eq!(&s.name, &"dep".to_owned());
-                    assert_eq!(default, &"test".to_owned());
-                }
-                StepKind::SingleSelect { default, values } => {
-                    assert_eq!(&s.name, &"language".to_owned());
-                    assert_eq!(default, &"java".to_owned());
-                    assert_eq!(
-                        values,
-                        &vec![
-                            Item::new_default("java".to_owned(), "Java".to_owned()),
-                            Item::new_default("kotlin".to_owned(), "Kotlin".to_owned()),
-                            Item::new_default("groovy".to_owned(), "Groovy".to_owned())
-                        ]
-                    );
-                }
-                StepKind::Action { .. } => {
-                    panic!("not in test data")
-                }
-                StepKind::MultiSelect { .. } => {
-                    panic!("not in test data")
-                }
-            })
-            .collect::<()>();
+        steps.iter().for_each(|s| match &s.kind {
+            StepKind::Text { default } => {
+                assert_eq!(&s.name, &"dep".to_owned());
+                assert_eq!(default, &"test".to_owned());
+            }
+            StepKind::SingleSelect { default, values } => {
+                assert_eq!(&s.name, &"language".to_owned());
+                assert_eq!(default, &"java".to_owned());
+                assert_eq!(
+                    values,
+                    &vec![
+                        Item::new_default("java".to_owned(), "Java".to_owned()),
+                        Item::new_default("kotlin".to_owned(), "Kotlin".to_owned()),
+                        Item::new_default("groovy".to_owned(), "Groovy".to_owned())
+                    ]
+                );
+            }
+            StepKind::Action { .. } => {
+                panic!("not in test data")
+            }
+            StepKind::MultiSelect { .. } => {
+                panic!("not in test data")
+            }
+        });
     }
 
     #[test]
@@ -743,10 +807,46 @@ mod test {
                 "content": "com.example.demo"
             }
         });
-        let steps = Step::from_json(json);
+        let steps = from_json(json);
         assert!(steps.is_ok());
-        let steps = steps.unwrap();
+        let mut steps = steps.unwrap();
 
         assert_eq!(steps.len(), 12);
+
+        Step::sort_by_order(&mut steps, DEFAULT_STEP_ORDER);
+        let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "type",
+                "language",
+                "bootVersion",
+                "groupId",
+                "artifactId",
+                "name",
+                "description",
+                "packageName",
+                "packaging",
+                "javaVersion",
+                "dependencies",
+                "version",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_custom_order_keeps_unknown_steps_stable() {
+        let text = |name: &str| Step {
+            name: name.to_owned(),
+            kind: StepKind::Text {
+                default: String::new(),
+            },
+        };
+        let mut steps = vec![text("b"), text("x"), text("a"), text("y"), text("c")];
+
+        Step::sort_by_order(&mut steps, &["c", "a", "b"]);
+
+        let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, vec!["c", "a", "b", "x", "y"]);
     }
 }
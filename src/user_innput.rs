@@ -1,28 +1,119 @@
 use anyhow::Result;
-use inquire::{MultiSelect, Select, Text};
+use inquire::{validator::Validation, InquireError, Select, Text};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
 
 use crate::{
+    args::OutputFormat,
+    list::Table,
+    picker::{self, selected_summary},
     request::ResponseStep,
-    steps::{Item, Step, StepKind},
+    steps::{Item, ItemKind, Step, StepKind},
+    validate,
+    version::Version,
 };
 
-fn get_multi_select(name: &str, values: &[Item]) -> Result<String> {
-    Ok(MultiSelect::new(
-        format!("Select the {} you want:", name).as_str(),
-        values.to_vec(),
-    )
-    .with_page_size(11)
-    .with_formatter(&|a| {
-        a.iter()
-            .map(|x| x.value.id.to_owned())
-            .collect::<Vec<String>>()
-            .join(", ")
-    })
-    .prompt()?
-    .iter()
-    .map(|x| x.id.to_owned())
-    .collect::<Vec<String>>()
-    .join(","))
+const DONE: &str = "Done";
+const ALL: &str = "All (type to filter)";
+
+/// The dependency groups in metadata order.
+fn groups(values: &[Item]) -> Vec<&str> {
+    let mut groups: Vec<&str> = Vec::new();
+    for item in values {
+        if let ItemKind::Dependency(group) = &item.kind {
+            if !groups.contains(&group.as_str()) {
+                groups.push(group);
+            }
+        }
+    }
+    groups
+}
+
+fn in_group(item: &Item, group: &str) -> bool {
+    matches!(&item.kind, ItemKind::Dependency(g) if g == group)
+}
+
+fn group_label(group: &str, values: &[Item], selected: &[String]) -> String {
+    let items: Vec<&Item> = values.iter().filter(|item| in_group(item, group)).collect();
+    let picked = items
+        .iter()
+        .filter(|item| selected.contains(&item.id))
+        .count();
+    if picked == 0 {
+        format!("{} ({})", group, items.len())
+    } else {
+        format!("{} ({}, {} selected)", group, items.len(), picked)
+    }
+}
+
+/// A two-level picker: the dependency groups first, or every dependency to
+/// filter across groups, then a multi-select of their dependencies.
+fn get_multi_select(
+    name: &str,
+    values: &[Item],
+    selected: &str,
+    boot_version: Option<&Version>,
+) -> Result<String> {
+    let (values, incompatible): (Vec<Item>, Vec<Item>) = values
+        .iter()
+        .cloned()
+        .partition(|item| boot_version.is_none_or(|v| item.is_compatible_with(v)));
+    let hidden = boot_version.filter(|_| !incompatible.is_empty()).map(|v| {
+        format!(
+            "{} {} hidden as they are not compatible with Spring Boot {}",
+            incompatible.len(),
+            name,
+            v
+        )
+    });
+    let mut selected: Vec<String> = selected
+        .split(',')
+        .map(str::trim)
+        .filter(|id| values.iter().any(|item| item.id == *id))
+        .map(str::to_owned)
+        .collect();
+    let groups = groups(&values);
+
+    let message = format!("Select the {} you want:", name);
+    loop {
+        let mut entries = vec![DONE.to_owned(), ALL.to_owned()];
+        entries.extend(
+            groups
+                .iter()
+                .map(|group| group_label(group, &values, &selected)),
+        );
+        let help = match &hidden {
+            Some(hidden) => format!("{}, {}", selected_summary(&selected), hidden),
+            None => selected_summary(&selected),
+        };
+        let summary = selected.join(", ");
+        let choice = Select::new(&message, entries)
+            .with_help_message(&help)
+            .with_page_size(12)
+            .with_formatter(&|option| match option.index {
+                0 => summary.clone(),
+                _ => option.value.to_owned(),
+            })
+            .raw_prompt()?;
+        let items: Vec<&Item> = match choice.index {
+            0 => return Ok(selected.join(",")),
+            1 => values.iter().collect(),
+            index => values
+                .iter()
+                .filter(|item| in_group(item, groups[index - 2]))
+                .collect(),
+        };
+        picker::pick(
+            &message,
+            &items,
+            choice.index == 1,
+            hidden.as_deref(),
+            &mut selected,
+        )?;
+    }
 }
 
 fn get_single_select(name: &str, values: &[Item], default: &str) -> Result<String> {
@@ -37,27 +128,526 @@ fn get_single_select(name: &str, values: &[Item], default: &str) -> Result<Strin
 }
 
 fn get_text(name: &str, default: &str) -> Result<String> {
+    let step = name.to_owned();
     Ok(Text::new(format!("What {} do you want:", name).as_str())
         .with_default(default)
+        .with_validator(move |input: &str| {
+            Ok(match validate::check_text(&step, input) {
+                Some(reason) => Validation::Invalid(reason.into()),
+                None => Validation::Valid,
+            })
+        })
         .prompt()?)
 }
 
-pub(crate) fn get_user_input(step: &Step, prefill: Option<&str>) -> Result<ResponseStep> {
-    let response = if let Some(value) = prefill {
-        value.to_string()
-    } else {
-        match &step.kind {
-            StepKind::Text { default } => get_text(&step.name, default)?,
-            StepKind::SingleSelect { default, values } => {
-                get_single_select(&step.name, values, default)?
+/// Turns `com.my-company.demo-app` into the valid Java package `com.mycompany.demoapp`.
+fn package_name(group_id: &str, artifact_id: &str) -> String {
+    format!("{}.{}", group_id, artifact_id)
+        .split('.')
+        .map(|segment| {
+            segment
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == '_')
+                .collect::<String>()
+        })
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            if segment.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("_{}", segment)
+            } else if validate::JAVA_KEYWORDS.contains(&segment.as_str()) {
+                format!("{}_", segment)
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn answer<'a>(previous: &'a [ResponseStep], step: &str) -> Option<&'a str> {
+    previous
+        .iter()
+        .find(|r| r.step.name == step)
+        .map(|r| r.response.as_str())
+        .filter(|response| !response.is_empty())
+}
+
+/// The default of a step, derived from earlier answers like the web UI does for
+/// `packageName` and `name` and otherwise the one sent by the server.
+pub(crate) fn default_for(step: &Step, previous: &[ResponseStep]) -> String {
+    let derived = match step.name.as_str() {
+        "packageName" => answer(previous, "groupId")
+            .zip(answer(previous, "artifactId"))
+            .map(|(group_id, artifact_id)| package_name(group_id, artifact_id)),
+        "name" => answer(previous, "artifactId").map(str::to_owned),
+        _ => None,
+    };
+    match (&step.kind, derived) {
+        (StepKind::Text { .. }, Some(derived)) => derived,
+        (StepKind::Text { default }, None) => default.clone(),
+        (StepKind::SingleSelect { default, .. }, _) | (StepKind::Action { default, .. }, _) => {
+            default.clone()
+        }
+        (StepKind::MultiSelect { .. }, _) => String::new(),
+    }
+}
+
+/// The Spring Boot version picked in an earlier step, if it could be parsed.
+fn selected_boot_version(previous: &[ResponseStep]) -> Option<Version> {
+    previous
+        .iter()
+        .find(|r| r.step.name == "bootVersion")
+        .and_then(|r| r.response.parse().ok())
+}
+
+/// Whether the user pressed Esc to leave a prompt.
+fn is_cancel(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<InquireError>(),
+        Some(InquireError::OperationCanceled)
+    )
+}
+
+/// Answers every step, from its prefill, its default in non-interactive mode or
+/// by prompting the user. Seeds replace the default a step starts from, like
+/// dependencies to preselect.
+pub(crate) fn get_user_inputs(
+    steps: Vec<Step>,
+    prefills: &HashMap<String, String>,
+    seeds: &HashMap<String, String>,
+    non_interactive: bool,
+) -> Result<Vec<ResponseStep>> {
+    answer_steps(steps, prefills, seeds, non_interactive, prompt)
+}
+
+/// Walks the steps as a state machine: Esc on a prompt goes back to the
+/// previous prompted step, which starts from the answer given before.
+fn answer_steps(
+    steps: Vec<Step>,
+    prefills: &HashMap<String, String>,
+    seeds: &HashMap<String, String>,
+    non_interactive: bool,
+    mut ask: impl FnMut(&Step, &str, &[ResponseStep]) -> Result<String>,
+) -> Result<Vec<ResponseStep>> {
+    let prompted = |step: &Step| !non_interactive && !prefills.contains_key(&step.name);
+    let mut earlier: Vec<Option<String>> = steps
+        .iter()
+        .map(|step| seeds.get(&step.name).cloned())
+        .collect();
+    let mut responses: Vec<ResponseStep> = Vec::with_capacity(steps.len());
+    let mut index = 0;
+    while index < steps.len() {
+        responses.truncate(index);
+        let step = &steps[index];
+        let response = match prefills.get(&step.name) {
+            Some(value) => value.clone(),
+            None if non_interactive => earlier[index]
+                .clone()
+                .unwrap_or_else(|| default_for(step, &responses)),
+            None => {
+                let default = earlier[index]
+                    .clone()
+                    .unwrap_or_else(|| default_for(step, &responses));
+                match ask(step, &default, &responses) {
+                    Ok(response) => response,
+                    Err(err) if is_cancel(&err) => {
+                        // Esc on the first prompt has nowhere to go back to
+                        if let Some(back) = (0..index).rev().find(|&i| prompted(&steps[i])) {
+                            index = back;
+                        }
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                }
             }
-            StepKind::Action { default, values } => get_single_select(&step.name, values, default)?,
-            StepKind::MultiSelect { values } => get_multi_select(&step.name, values)?,
+        };
+        earlier[index] = Some(response.clone());
+        responses.push(ResponseStep {
+            step: step.clone(),
+            response,
+        });
+        index += 1;
+    }
+    Ok(responses)
+}
+
+/// Prompts for a step starting from `default`, which for dependencies is the
+/// comma separated ids to preselect.
+fn prompt(step: &Step, default: &str, previous: &[ResponseStep]) -> Result<String> {
+    match &step.kind {
+        StepKind::Text { .. } => get_text(&step.name, default),
+        StepKind::SingleSelect { values, .. } | StepKind::Action { values, .. } => {
+            get_single_select(&step.name, values, default)
         }
+        StepKind::MultiSelect { values } => get_multi_select(
+            &step.name,
+            values,
+            default,
+            selected_boot_version(previous).as_ref(),
+        ),
+    }
+}
+
+fn summary(responses: &[ResponseStep]) -> Table {
+    Table {
+        columns: vec![("step", "STEP"), ("answer", "ANSWER")],
+        rows: responses
+            .iter()
+            .map(|r| vec![json!(r.step.name), json!(r.response)])
+            .collect(),
+    }
+}
+
+const CONFIRM: &str = "Generate the project";
+const EDIT: &str = "Edit an answer";
+const ABORT: &str = "Abort";
+
+/// Shows every answer and lets the user change them until they confirm,
+/// returning false if they abort instead.
+pub(crate) fn review(responses: &mut [ResponseStep]) -> Result<bool> {
+    loop {
+        let mut stdout = io::stdout();
+        writeln!(stdout)?;
+        summary(responses).print(OutputFormat::Table, &mut stdout)?;
+        // Esc on the summary aborts like the Abort choice
+        match Select::new("Does this look right?", vec![CONFIRM, EDIT, ABORT]).prompt() {
+            Ok(CONFIRM) => return Ok(true),
+            Ok(ABORT) | Err(InquireError::OperationCanceled) => return Ok(false),
+            Ok(_) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let answers: Vec<String> = responses
+            .iter()
+            .map(|r| format!("{}: {}", r.step.name, r.response))
+            .collect();
+        // Esc while editing goes back to the summary, keeping the answer as it was
+        let index = match Select::new("Which answer do you want to change?", answers)
+            .with_page_size(12)
+            .raw_prompt()
+        {
+            Ok(choice) => choice.index,
+            Err(InquireError::OperationCanceled) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        let edited = &responses[index];
+        match prompt(&edited.step, &edited.response, &responses[..index]) {
+            Ok(response) => {
+                let dropped = apply_edit(responses, index, response);
+                if !dropped.is_empty() {
+                    println!(
+                        "removed {} as they are not compatible with the new Spring Boot version",
+                        dropped.join(", ")
+                    );
+                }
+            }
+            Err(err) if is_cancel(&err) => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Stores an edited answer and updates the answers depending on it: derived
+/// defaults the user kept, like `packageName`, and dependencies no longer
+/// compatible with the Spring Boot version, which are returned.
+fn apply_edit(responses: &mut [ResponseStep], index: usize, response: String) -> Vec<String> {
+    let defaults: Vec<String> = responses
+        .iter()
+        .map(|r| default_for(&r.step, responses))
+        .collect();
+    responses[index].response = response;
+    for (i, default) in defaults.iter().enumerate() {
+        let kept_default = matches!(responses[i].step.kind, StepKind::Text { .. })
+            && responses[i].response == *default;
+        if i != index && kept_default {
+            responses[i].response = default_for(&responses[i].step, responses);
+        }
+    }
+
+    let Some(version) = selected_boot_version(responses) else {
+        return Vec::new();
     };
+    let mut dropped = Vec::new();
+    for r in responses.iter_mut() {
+        let StepKind::MultiSelect { values } = &r.step.kind else {
+            continue;
+        };
+        let (kept, incompatible): (Vec<&str>, Vec<&str>) = r
+            .response
+            .split(',')
+            .filter(|id| !id.is_empty())
+            .partition(|id| {
+                values
+                    .iter()
+                    .find(|item| item.id == *id)
+                    .is_none_or(|item| item.is_compatible_with(&version))
+            });
+        dropped.extend(incompatible.into_iter().map(str::to_owned));
+        r.response = kept.join(",");
+    }
+    dropped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(name: &str, default: &str) -> Step {
+        Step {
+            name: name.to_owned(),
+            kind: StepKind::Text {
+                default: default.to_owned(),
+            },
+        }
+    }
+
+    fn answered(name: &str, response: &str) -> ResponseStep {
+        ResponseStep {
+            step: text(name, ""),
+            response: response.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_package_name() {
+        assert_eq!(package_name("com.example", "demo"), "com.example.demo");
+        assert_eq!(
+            package_name("com.my-company", "demo-app"),
+            "com.mycompany.demoapp"
+        );
+        assert_eq!(package_name("org.acme.", "2fa"), "org.acme._2fa");
+        assert_eq!(package_name("io.new", "app"), "io.new_.app");
+    }
+
+    #[test]
+    fn test_default_derived_from_previous_answers() {
+        let previous = [
+            answered("groupId", "org.acme"),
+            answered("artifactId", "billing-service"),
+        ];
+
+        assert_eq!(
+            default_for(&text("packageName", "com.example.demo"), &previous),
+            "org.acme.billingservice"
+        );
+        assert_eq!(
+            default_for(&text("name", "demo"), &previous),
+            "billing-service"
+        );
+        assert_eq!(
+            default_for(&text("description", "Demo project"), &previous),
+            "Demo project"
+        );
+    }
+
+    #[test]
+    fn test_default_without_previous_answers() {
+        let previous = [answered("artifactId", "billing")];
+
+        assert_eq!(
+            default_for(&text("packageName", "com.example.demo"), &previous),
+            "com.example.demo"
+        );
+        assert_eq!(default_for(&text("name", "demo"), &[]), "demo");
+    }
+
+    #[test]
+    fn test_summary() {
+        let responses = [
+            answered("groupId", "org.acme"),
+            answered("artifactId", "billing"),
+            answered("dependencies", ""),
+        ];
+
+        let mut out = Vec::new();
+        summary(&responses)
+            .print(OutputFormat::Table, &mut out)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "STEP          ANSWER\n\
+             groupId       org.acme\n\
+             artifactId    billing\n\
+             dependencies\n"
+        );
+    }
+
+    #[test]
+    fn test_esc_goes_back_to_previous_prompt() {
+        let steps = vec![
+            text("groupId", "com.example"),
+            text("artifactId", "demo"),
+            text("version", "0.0.1-SNAPSHOT"),
+            text("name", "demo"),
+        ];
+        let prefills: HashMap<String, String> = [("version".to_owned(), "1.0.0".to_owned())]
+            .into_iter()
+            .collect();
+        // Esc on the first prompt stays, Esc on name skips the prefilled version
+        // back to artifactId, whose earlier answer is offered again while the
+        // unanswered name follows the new artifactId
+        let mut script = vec![
+            Err(InquireError::OperationCanceled),
+            Ok("org.acme"),
+            Ok("billing"),
+            Err(InquireError::OperationCanceled),
+            Ok("billing-service"),
+            Ok("Billing"),
+        ]
+        .into_iter();
+        let mut asked = Vec::new();
+
+        let responses = answer_steps(
+            steps,
+            &prefills,
+            &HashMap::new(),
+            false,
+            |step, default, _| {
+                asked.push(format!("{}={}", step.name, default));
+                Ok(script.next().unwrap()?.to_owned())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            asked,
+            vec![
+                "groupId=com.example",
+                "groupId=com.example",
+                "artifactId=demo",
+                "name=billing",
+                "artifactId=billing",
+                "name=billing-service",
+            ]
+        );
+        let answers: Vec<(&str, &str)> = responses
+            .iter()
+            .map(|r| (r.step.name.as_str(), r.response.as_str()))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![
+                ("groupId", "org.acme"),
+                ("artifactId", "billing-service"),
+                ("version", "1.0.0"),
+                ("name", "Billing"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_interactive_uses_defaults() {
+        let steps = vec![text("artifactId", "demo"), text("name", "demo")];
+        let prefills: HashMap<String, String> = [("artifactId".to_owned(), "billing".to_owned())]
+            .into_iter()
+            .collect();
+
+        let responses = answer_steps(steps, &prefills, &HashMap::new(), true, |_, _, _| {
+            panic!("non-interactive mode must not prompt")
+        })
+        .unwrap();
+
+        assert_eq!(responses[1].response, "billing");
+    }
+
+    #[test]
+    fn test_seeds_are_preselected() {
+        let steps = vec![
+            Step {
+                name: "dependencies".to_owned(),
+                kind: StepKind::MultiSelect {
+                    values: dependencies(),
+                },
+            },
+            text("name", "demo"),
+        ];
+        let seeds: HashMap<String, String> =
+            [("dependencies".to_owned(), "web,data-jpa".to_owned())]
+                .into_iter()
+                .collect();
+        let mut asked = Vec::new();
+
+        let responses = answer_steps(steps, &HashMap::new(), &seeds, false, |step, default, _| {
+            asked.push(format!("{}={}", step.name, default));
+            Ok(format!("{},postgresql", default))
+        })
+        .unwrap();
+
+        assert_eq!(asked[0], "dependencies=web,data-jpa");
+        assert_eq!(responses[0].response, "web,data-jpa,postgresql");
+    }
+
+    fn dependencies() -> Vec<Item> {
+        [
+            ("web", "Spring Web", "Web"),
+            ("data-jpa", "Spring Data JPA", "SQL"),
+            ("webflux", "Spring Reactive Web", "Web"),
+            ("postgresql", "PostgreSQL Driver", "SQL"),
+        ]
+        .iter()
+        .map(|(id, name, group)| {
+            Item::new_dependency(id.to_string(), name.to_string(), group.to_string())
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_group_labels() {
+        let values = dependencies();
+        let selected = vec!["postgresql".to_owned()];
+
+        assert_eq!(groups(&values), vec!["Web", "SQL"]);
+        assert_eq!(group_label("Web", &values, &selected), "Web (2)");
+        assert_eq!(
+            group_label("SQL", &values, &selected),
+            "SQL (2, 1 selected)"
+        );
+    }
+
+    #[test]
+    fn test_edit_updates_dependent_answers() {
+        let boot_version = Step {
+            name: "bootVersion".to_owned(),
+            kind: StepKind::SingleSelect {
+                default: "3.4.2".to_owned(),
+                values: ["3.5.0", "3.4.2"]
+                    .iter()
+                    .map(|v| Item::new_default(v.to_string(), v.to_string()))
+                    .collect(),
+            },
+        };
+        let mut values = dependencies();
+        values[1] = values[1]
+            .clone()
+            .with_compatibility_range(Some("[3.3.0,3.5.0-M1)".to_owned()));
+        let answer = |step: Step, response: &str| ResponseStep {
+            step,
+            response: response.to_owned(),
+        };
+        let mut responses = vec![
+            answer(boot_version, "3.4.2"),
+            answered("groupId", "org.acme"),
+            answered("artifactId", "billing"),
+            answered("name", "Billing"),
+            answered("packageName", "org.acme.billing"),
+            answer(
+                Step {
+                    name: "dependencies".to_owned(),
+                    kind: StepKind::MultiSelect { values },
+                },
+                "web,data-jpa",
+            ),
+        ];
+
+        assert!(apply_edit(&mut responses, 2, "invoices".to_owned()).is_empty());
+        assert_eq!(responses[4].response, "org.acme.invoices");
+        assert_eq!(responses[3].response, "Billing");
 
-    Ok(ResponseStep {
-        step: step.to_owned(),
-        response,
-    })
+        assert_eq!(
+            apply_edit(&mut responses, 0, "3.5.0".to_owned()),
+            vec!["data-jpa"]
+        );
+        assert_eq!(responses[5].response, "web");
+    }
 }
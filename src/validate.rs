@@ -0,0 +1,386 @@
+use anyhow::Result;
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    error::CliError,
+    steps::{Item, Step, StepKind},
+};
+
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Reserved words that cannot be used as Java package segments.
+pub(crate) const JAVA_KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// A prefilled value that is not one of the options offered by its step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue {
+    pub step: String,
+    pub value: String,
+    pub suggestions: Vec<String>,
+    /// Why the value is rejected, for text steps with known rules.
+    pub reason: Option<String>,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} '{}'", self.step, self.value)?;
+        if let Some(reason) = &self.reason {
+            write!(f, ", {}", reason)?;
+        }
+        if !self.suggestions.is_empty() {
+            write!(f, ", did you mean {}?", self.suggestions.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+fn is_maven_coordinate(value: &str) -> bool {
+    value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn is_java_identifier(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || matches!(c, '_' | '$'))
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '$'))
+}
+
+/// Why a text value breaks the Maven or Java rules of its step, `None` when it
+/// is valid or the step has no known rules.
+pub fn check_text(step: &str, value: &str) -> Option<String> {
+    let empty_segment = || value.split('.').any(str::is_empty);
+    let reason = match step {
+        "groupId" | "artifactId" | "packageName" | "version" if value.is_empty() => {
+            "must not be empty".to_owned()
+        }
+        "groupId" | "artifactId" if !is_maven_coordinate(value) => {
+            "may only contain letters, digits, '.', '_' and '-'".to_owned()
+        }
+        "groupId" | "packageName" if empty_segment() => {
+            "must not start or end with a dot or contain consecutive dots".to_owned()
+        }
+        "packageName" => {
+            let segment = value
+                .split('.')
+                .find(|segment| !is_java_identifier(segment) || JAVA_KEYWORDS.contains(segment))?;
+            if is_java_identifier(segment) {
+                format!("'{}' is a Java keyword", segment)
+            } else {
+                format!("'{}' is not a valid Java identifier", segment)
+            }
+        }
+        "version"
+            if !value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+')) =>
+        {
+            "may only contain letters, digits, '.', '_', '-' and '+'".to_owned()
+        }
+        _ => return None,
+    };
+    Some(reason)
+}
+
+/// Checks a prefilled value against the options of its step, or against the
+/// Maven and Java rules for text steps.
+pub fn check_value(step: &Step, value: &str) -> Vec<InvalidValue> {
+    let values = match &step.kind {
+        StepKind::Text { .. } => {
+            return check_text(&step.name, value)
+                .map(|reason| InvalidValue {
+                    step: step.name.clone(),
+                    value: value.to_owned(),
+                    suggestions: Vec::new(),
+                    reason: Some(reason),
+                })
+                .into_iter()
+                .collect();
+        }
+        StepKind::SingleSelect { values, .. } | StepKind::Action { values, .. } => {
+            return check_id(&step.name, values, value).into_iter().collect();
+        }
+        StepKind::MultiSelect { values } => values,
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .filter_map(|id| check_id(&step.name, values, id))
+        .collect()
+}
+
+fn check_id(step: &str, values: &[Item], id: &str) -> Option<InvalidValue> {
+    if values.iter().any(|item| item.id == id) {
+        return None;
+    }
+    Some(InvalidValue {
+        step: step.to_owned(),
+        value: id.to_owned(),
+        suggestions: suggestions(values.iter().map(|item| item.id.as_str()), id),
+        reason: None,
+    })
+}
+
+/// The ids closest to `id` by edit distance, ignoring ones too far off to be a typo.
+fn suggestions<'a>(ids: impl IntoIterator<Item = &'a str>, id: &str) -> Vec<String> {
+    let max_distance = (id.chars().count() / 3).max(1);
+    let mut candidates: Vec<(usize, &str)> = ids
+        .into_iter()
+        .map(|candidate| (strsim::damerau_levenshtein(id, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort_by_key(|(distance, _)| *distance);
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, id)| id.to_owned())
+        .collect()
+}
+
+/// Fails with every invalid value at once, exiting as an argument error.
+fn fail_on(invalid: Vec<InvalidValue>) -> Result<()> {
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    let message = invalid
+        .iter()
+        .map(InvalidValue::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(CliError::Invalid(message).into())
+}
+
+/// Checks every prefilled value against its step, failing with all invalid values at once.
+pub fn validate_prefills(steps: &[Step], values: &HashMap<String, String>) -> Result<()> {
+    let invalid: Vec<InvalidValue> = steps
+        .iter()
+        .filter_map(|step| values.get(&step.name).map(|value| check_value(step, value)))
+        .flatten()
+        .collect();
+
+    fail_on(invalid)
+}
+
+/// Checks that every id names a step offered by the server.
+pub fn validate_step_ids<'a>(steps: &[Step], ids: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    let invalid: Vec<InvalidValue> = ids
+        .into_iter()
+        .filter(|id| !steps.iter().any(|step| step.name == *id))
+        .map(|id| InvalidValue {
+            step: "step".to_owned(),
+            value: id.to_owned(),
+            suggestions: suggestions(steps.iter().map(|step| step.name.as_str()), id),
+            reason: None,
+        })
+        .collect();
+
+    fail_on(invalid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn java_versions() -> Step {
+        Step {
+            name: "javaVersion".to_owned(),
+            kind: StepKind::SingleSelect {
+                default: "17".to_owned(),
+                values: ["24", "21", "17"]
+                    .iter()
+                    .map(|v| Item::new_default(v.to_string(), v.to_string()))
+                    .collect(),
+            },
+        }
+    }
+
+    fn dependencies() -> Step {
+        Step {
+            name: "dependencies".to_owned(),
+            kind: StepKind::MultiSelect {
+                values: [
+                    ("web", "Web"),
+                    ("data-jpa", "SQL"),
+                    ("data-jdbc", "SQL"),
+                    ("security", "Security"),
+                ]
+                .iter()
+                .map(|(id, group)| {
+                    Item::new_dependency(id.to_string(), id.to_string(), group.to_string())
+                })
+                .collect(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_valid_values() {
+        assert!(check_value(&java_versions(), "21").is_empty());
+        assert!(check_value(&dependencies(), "web,data-jpa").is_empty());
+        assert!(check_value(&dependencies(), "").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_dependency_suggests_closest() {
+        let invalid = check_value(&dependencies(), "web, data-jap,nope");
+
+        assert_eq!(
+            invalid,
+            vec![
+                InvalidValue {
+                    step: "dependencies".to_owned(),
+                    value: "data-jap".to_owned(),
+                    suggestions: vec!["data-jpa".to_owned()],
+                    reason: None,
+                },
+                InvalidValue {
+                    step: "dependencies".to_owned(),
+                    value: "nope".to_owned(),
+                    suggestions: vec![],
+                    reason: None,
+                },
+            ]
+        );
+        assert_eq!(
+            invalid[0].to_string(),
+            "invalid dependencies 'data-jap', did you mean data-jpa?"
+        );
+        assert_eq!(invalid[1].to_string(), "invalid dependencies 'nope'");
+    }
+
+    #[test]
+    fn test_validate_prefills_reports_every_value() {
+        let values: HashMap<String, String> = [
+            ("javaVersion".to_owned(), "22".to_owned()),
+            ("dependencies".to_owned(), "web,data-jap".to_owned()),
+            ("name".to_owned(), "anything".to_owned()),
+        ]
+        .into_iter()
+        .collect();
+
+        let err = validate_prefills(&[java_versions(), dependencies()], &values).unwrap_err();
+
+        assert_eq!(CliError::exit_code_of(&err), 2);
+        assert_eq!(
+            err.to_string(),
+            "invalid javaVersion '22', did you mean 24, 21?\n\
+             invalid dependencies 'data-jap', did you mean data-jpa?"
+        );
+    }
+
+    #[test]
+    fn test_validate_step_ids() {
+        let steps = [java_versions(), dependencies()];
+
+        assert!(validate_step_ids(&steps, ["javaVersion", "dependencies"]).is_ok());
+        assert_eq!(
+            validate_step_ids(&steps, ["javaVersions", "configurationFileFormat"])
+                .unwrap_err()
+                .to_string(),
+            "invalid step 'javaVersions', did you mean javaVersion?\n\
+             invalid step 'configurationFileFormat'"
+        );
+    }
+
+    #[test]
+    fn test_check_text() {
+        assert_eq!(check_text("groupId", "com.example"), None);
+        assert_eq!(check_text("artifactId", "demo-app_2"), None);
+        assert_eq!(check_text("packageName", "com.example.demo_app"), None);
+        assert_eq!(check_text("version", "0.0.1-SNAPSHOT"), None);
+        assert_eq!(check_text("description", "anything goes.."), None);
+
+        assert_eq!(
+            check_text("groupId", "com..example").unwrap(),
+            "must not start or end with a dot or contain consecutive dots"
+        );
+        assert_eq!(
+            check_text("artifactId", "demo app").unwrap(),
+            "may only contain letters, digits, '.', '_' and '-'"
+        );
+        assert_eq!(
+            check_text("packageName", "com.example.new").unwrap(),
+            "'new' is a Java keyword"
+        );
+        assert_eq!(
+            check_text("packageName", "com.example.demo-app").unwrap(),
+            "'demo-app' is not a valid Java identifier"
+        );
+        assert_eq!(check_text("version", "").unwrap(), "must not be empty");
+    }
+
+    #[test]
+    fn test_validate_prefills_checks_text_rules() {
+        let group_id = Step {
+            name: "groupId".to_owned(),
+            kind: StepKind::Text {
+                default: "com.example".to_owned(),
+            },
+        };
+        let values: HashMap<String, String> = [("groupId".to_owned(), "com..example".to_owned())]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            validate_prefills(&[group_id], &values).unwrap_err().to_string(),
+            "invalid groupId 'com..example', must not start or end with a dot or contain consecutive dots"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::{bail, Context, Result};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// Qualifiers ordered the way Spring orders them: milestones before release
+/// candidates before snapshots before the final release.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Qualifier {
+    Milestone(u32),
+    ReleaseCandidate(u32),
+    Snapshot,
+    Release,
+}
+
+/// A Spring version such as `3.4.2`, `3.5.0-M1`, `3.4.3-SNAPSHOT` or `2.7.0.RELEASE`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub qualifier: Qualifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bound {
+    pub version: Version,
+    pub inclusive: bool,
+}
+
+/// A Spring version range, `3.3.0` meaning "3.3.0 and later" and
+/// `[3.3.0,3.5.0-M1)` using the usual inclusive/exclusive brackets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    pub lower: Bound,
+    pub upper: Option<Bound>,
+}
+
+impl FromStr for Qualifier {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let number = |digits: &str| -> Result<u32> {
+            if digits.is_empty() {
+                Ok(0)
+            } else {
+                digits
+                    .parse()
+                    .with_context(|| format!("invalid qualifier '{}'", s))
+            }
+        };
+        match s.to_ascii_uppercase().as_str() {
+            "" | "RELEASE" | "FINAL" | "GA" => Ok(Qualifier::Release),
+            "SNAPSHOT" | "BUILD-SNAPSHOT" => Ok(Qualifier::Snapshot),
+            q if q.starts_with("RC") => Ok(Qualifier::ReleaseCandidate(number(&q[2..])?)),
+            q if q.starts_with('M') => Ok(Qualifier::Milestone(number(&q[1..])?)),
+            _ => bail!("invalid qualifier '{}'", s),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let mut parts = s.splitn(4, '.');
+        let mut number = || -> Result<(u32, Option<&str>)> {
+            let part = parts
+                .next()
+                .with_context(|| format!("invalid version '{}'", s))?;
+            let (digits, qualifier) = match part.split_once('-') {
+                Some((digits, qualifier)) => (digits, Some(qualifier)),
+                None => (part, None),
+            };
+            let n = digits
+                .parse()
+                .with_context(|| format!("invalid version '{}'", s))?;
+            Ok((n, qualifier))
+        };
+
+        let (major, _) = number()?;
+        let (minor, _) = number()?;
+        let (patch, dash_qualifier) = number()?;
+        let qualifier = dash_qualifier.or(parts.next()).unwrap_or_default();
+
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            qualifier: qualifier.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        match self.qualifier {
+            Qualifier::Milestone(n) => write!(f, "-M{}", n),
+            Qualifier::ReleaseCandidate(n) => write!(f, "-RC{}", n),
+            Qualifier::Snapshot => write!(f, "-SNAPSHOT"),
+            Qualifier::Release => Ok(()),
+        }
+    }
+}
+
+impl FromStr for VersionRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let lower_inclusive = match s.chars().next() {
+            Some('[') => true,
+            Some('(') => false,
+            _ => {
+                return Ok(VersionRange {
+                    lower: Bound {
+                        version: s.parse()?,
+                        inclusive: true,
+                    },
+                    upper: None,
+                })
+            }
+        };
+        let upper_inclusive = match s.chars().last() {
+            Some(']') => true,
+            Some(')') => false,
+            _ => bail!("invalid version range '{}'", s),
+        };
+        let (lower, upper) = s[1..s.len() - 1]
+            .split_once(',')
+            .with_context(|| format!("invalid version range '{}'", s))?;
+
+        Ok(VersionRange {
+            lower: Bound {
+                version: lower.parse()?,
+                inclusive: lower_inclusive,
+            },
+            upper: Some(Bound {
+                version: upper.parse()?,
+                inclusive: upper_inclusive,
+            }),
+        })
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.upper {
+            None => write!(f, ">={}", self.lower.version),
+            Some(upper) => write!(
+                f,
+                "{}{},{}{}",
+                if self.lower.inclusive { '[' } else { '(' },
+                self.lower.version,
+                upper.version,
+                if upper.inclusive { ']' } else { ')' }
+            ),
+        }
+    }
+}
+
+impl VersionRange {
+    pub fn contains(&self, version: &Version) -> bool {
+        let above_lower = match version.cmp(&self.lower.version) {
+            Ordering::Greater => true,
+            Ordering::Equal => self.lower.inclusive,
+            Ordering::Less => false,
+        };
+        let below_upper = match &self.upper {
+            None => true,
+            Some(upper) => match version.cmp(&upper.version) {
+                Ordering::Less => true,
+                Ordering::Equal => upper.inclusive,
+                Ordering::Greater => false,
+            },
+        };
+        above_lower && below_upper
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn version(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            version("3.5.0-M1"),
+            Version {
+                major: 3,
+                minor: 5,
+                patch: 0,
+                qualifier: Qualifier::Milestone(1),
+            }
+        );
+        assert_eq!(version("3.4.3-SNAPSHOT").qualifier, Qualifier::Snapshot);
+        assert_eq!(version("3.4.2").qualifier, Qualifier::Release);
+        assert_eq!(version("2.7.0.RELEASE").qualifier, Qualifier::Release);
+        assert_eq!(
+            version("2.0.0.RC2").qualifier,
+            Qualifier::ReleaseCandidate(2)
+        );
+        assert_eq!(
+            version("2.0.0.BUILD-SNAPSHOT").qualifier,
+            Qualifier::Snapshot
+        );
+        assert!("3.4".parse::<Version>().is_err());
+        assert!("3.4.x".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        let mut versions = [
+            version("3.5.0"),
+            version("3.5.0-SNAPSHOT"),
+            version("3.5.0-RC1"),
+            version("3.5.0-M2"),
+            version("3.5.0-M1"),
+            version("3.4.10"),
+            version("3.4.2"),
+        ];
+        versions.sort();
+
+        assert_eq!(
+            versions.iter().map(Version::to_string).collect::<Vec<_>>(),
+            vec![
+                "3.4.2",
+                "3.4.10",
+                "3.5.0-M1",
+                "3.5.0-M2",
+                "3.5.0-RC1",
+                "3.5.0-SNAPSHOT",
+                "3.5.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_range_contains() {
+        let range: VersionRange = "[3.3.0,3.5.0-M1)".parse().unwrap();
+
+        assert!(range.contains(&version("3.3.0")));
+        assert!(range.contains(&version("3.4.2")));
+        assert!(range.contains(&version("3.4.3-SNAPSHOT")));
+        assert!(!range.contains(&version("3.3.0-RC1")));
+        assert!(!range.contains(&version("3.5.0-M1")));
+        assert!(!range.contains(&version("3.5.0")));
+
+        let range: VersionRange = "(3.3.0,3.4.0]".parse().unwrap();
+        assert!(!range.contains(&version("3.3.0")));
+        assert!(range.contains(&version("3.4.0")));
+
+        let range: VersionRange = "3.4.0".parse().unwrap();
+        assert!(range.contains(&version("3.5.0-SNAPSHOT")));
+        assert!(!range.contains(&version("3.3.8")));
+        assert_eq!(range.to_string(), ">=3.4.0");
+    }
+}